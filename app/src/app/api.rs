@@ -0,0 +1,142 @@
+use gloo_net::http::{Request, RequestBuilder, Response};
+use js_sys::{Array, Uint8Array};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, FormData, HtmlAnchorElement, HtmlDocument, Url};
+
+use crate::app::i18n;
+
+/// 后端接口前缀，与 web 服务的 `/api` 路由对应
+const API_BASE: &str = "/api";
+
+/// 与后端 CSRF 校验的 cookie 及请求头对应
+const CSRF_COOKIE: &str = "XSRF-TOKEN";
+const CSRF_HEADER: &str = "X-XSRF-TOKEN";
+
+pub async fn get<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let resp = Request::get(&format!("{API_BASE}{path}"))
+        .header("Accept-Language", i18n::lang().code())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    parse(resp).await
+}
+
+pub async fn post<B: Serialize, T: DeserializeOwned>(path: &str, body: &B) -> Result<T, String> {
+    parse(send_post(path, body).await?).await
+}
+
+pub async fn put<B: Serialize, T: DeserializeOwned>(path: &str, body: &B) -> Result<T, String> {
+    let request = Request::put(&format!("{API_BASE}{path}"));
+    parse(send_json(request, body).await?).await
+}
+
+pub async fn delete<B: Serialize, T: DeserializeOwned>(path: &str, body: &B) -> Result<T, String> {
+    let request = Request::delete(&format!("{API_BASE}{path}"));
+    parse(send_json(request, body).await?).await
+}
+
+/// 上传 multipart 表单，boundary 由浏览器生成
+pub async fn upload<T: DeserializeOwned>(path: &str, form: FormData) -> Result<T, String> {
+    let resp = Request::post(&format!("{API_BASE}{path}"))
+        .header("Accept-Language", i18n::lang().code())
+        .header(CSRF_HEADER, &csrf_token())
+        .body(form)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    parse(resp).await
+}
+
+/// 下载后端生成的文件，保存为 filename
+pub async fn download<B: Serialize>(path: &str, body: &B, filename: &str) -> Result<(), String> {
+    let resp = send_post(path, body).await?;
+    if !resp.ok() {
+        return parse::<()>(resp).await;
+    }
+    let bytes = resp.binary().await.map_err(|e| e.to_string())?;
+    save(&bytes, filename).map_err(|e| format!("{e:?}"))
+}
+
+async fn send_post<B: Serialize>(path: &str, body: &B) -> Result<Response, String> {
+    send_json(Request::post(&format!("{API_BASE}{path}")), body).await
+}
+
+async fn send_json<B: Serialize>(request: RequestBuilder, body: &B) -> Result<Response, String> {
+    request
+        .header("Accept-Language", i18n::lang().code())
+        .header(CSRF_HEADER, &csrf_token())
+        .json(body)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 读取后端下发的 CSRF cookie，写请求需要原样放入请求头
+fn csrf_token() -> String {
+    let cookie = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.dyn_into::<HtmlDocument>().ok())
+        .and_then(|d| d.cookie().ok())
+        .unwrap_or_default();
+    cookie
+        .split(';')
+        .filter_map(|c| c.trim().split_once('='))
+        .find(|(name, _)| *name == CSRF_COOKIE)
+        .map(|(_, value)| value.to_string())
+        .unwrap_or_default()
+}
+
+/// 通过临时链接触发浏览器保存文件
+fn save(bytes: &[u8], filename: &str) -> Result<(), wasm_bindgen::JsValue> {
+    let parts = Array::of1(&Uint8Array::from(bytes));
+    let blob = Blob::new_with_u8_array_sequence(&parts)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("no document")?;
+    let a = document
+        .create_element("a")?
+        .unchecked_into::<HtmlAnchorElement>();
+    a.set_href(&url);
+    a.set_download(filename);
+    a.click();
+    Url::revoke_object_url(&url)
+}
+
+/// 后端统一的错误响应体
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: String,
+    message: String,
+    request_id: Option<String>,
+}
+
+async fn parse<T: DeserializeOwned>(resp: Response) -> Result<T, String> {
+    if !resp.ok() {
+        let text = resp.text().await.unwrap_or_default();
+        let e = match serde_json::from_str::<ErrorBody>(&text) {
+            Ok(body) => {
+                log::error!(
+                    "{} {}: [{}] {} (request_id: {})",
+                    resp.status(),
+                    resp.url(),
+                    body.code,
+                    body.message,
+                    body.request_id.unwrap_or_default()
+                );
+                body.message
+            }
+            Err(_) => {
+                let e = format!("{} {}: {}", resp.status(), resp.url(), text);
+                log::error!("{}", e);
+                e
+            }
+        };
+        return Err(e);
+    }
+    resp.json::<T>().await.map_err(|e| e.to_string())
+}
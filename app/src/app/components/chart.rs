@@ -0,0 +1,313 @@
+use serde::Deserialize;
+use yew::{html, Html};
+
+use crate::app::i18n::t;
+
+/// 画布大小，SVG 按 viewBox 缩放到容器宽度
+const WIDTH: f64 = 400.0;
+const HEIGHT: f64 = 240.0;
+/// 坐标轴与画布边缘的距离，留给刻度和分类文字
+const MARGIN: f64 = 36.0;
+/// 分类文字最多显示的个数，超出时间隔显示
+const MAX_TICKS: usize = 12;
+const PALETTE: [&str; 8] = [
+    "#485fc7", "#48c78e", "#f14668", "#ffb70f", "#3e8ed0", "#9b59b6", "#00d1b2", "#7a7a7a",
+];
+
+/// 对应后端 `/crud/aggregate` 返回的一个分组
+#[derive(Deserialize, PartialEq, Clone, Debug)]
+pub struct AggregateRow {
+    pub keys: Vec<Option<String>>,
+    pub labels: Vec<Option<String>>,
+    pub values: Vec<Option<f64>>,
+}
+
+impl AggregateRow {
+    /// 多个分组列的标签以 “/” 连接
+    fn category(&self) -> String {
+        self.labels
+            .iter()
+            .map(|label| label.as_deref().unwrap_or("-"))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    fn value(&self, measure: usize) -> f64 {
+        self.values
+            .get(measure)
+            .copied()
+            .flatten()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, PartialEq, Clone, Debug, Default)]
+pub struct AggregateResult {
+    pub dimensions: Vec<String>,
+    pub measures: Vec<String>,
+    pub rows: Vec<AggregateRow>,
+    pub truncated: bool,
+}
+
+/// 纵轴的取值范围，始终包含 0
+struct Scale {
+    min: f64,
+    max: f64,
+}
+
+impl Scale {
+    fn new(result: &AggregateResult) -> Self {
+        let values = result
+            .rows
+            .iter()
+            .flat_map(|row| row.values.iter().flatten().copied());
+        let (min, max) = values.fold((0.0_f64, 0.0_f64), |(min, max), v| (min.min(v), max.max(v)));
+        Scale {
+            min,
+            max: if max > min { max } else { min + 1.0 },
+        }
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        MARGIN + (self.max - value) / (self.max - self.min) * (HEIGHT - 2.0 * MARGIN)
+    }
+}
+
+/// 指标卡：第一组的各个聚合值
+pub fn kpi(result: &AggregateResult) -> Html {
+    let row = result.rows.first();
+
+    html! {
+        <nav class="level">
+            {for result.measures.iter().enumerate().map(|(i, measure)| {
+                let value = row.and_then(|r| r.values.get(i).copied().flatten());
+                html! {
+                    <div class="level-item has-text-centered">
+                        <div>
+                            <p class="heading">{measure}</p>
+                            <p class="title">{value.map(format_value).unwrap_or_else(|| "-".to_string())}</p>
+                        </div>
+                    </div>
+                }
+            })}
+        </nav>
+    }
+}
+
+/// 柱状图：每个聚合值一个系列，同一分类的柱子并排
+pub fn bar(result: &AggregateResult) -> Html {
+    if result.rows.is_empty() || result.measures.is_empty() {
+        return empty();
+    }
+    let scale = Scale::new(result);
+    let band = band(result.rows.len());
+    let width = band * 0.8 / result.measures.len() as f64;
+    let zero = scale.y(0.0);
+    let bars = result.rows.iter().enumerate().flat_map(|(i, row)| {
+        let scale = &scale;
+        result.measures.iter().enumerate().map(move |(m, measure)| {
+            let value = row.value(m);
+            let y = scale.y(value);
+            let x = MARGIN + band * (i as f64 + 0.1) + width * m as f64;
+            html! {
+                <rect x={num(x)} y={num(y.min(zero))} width={num(width)} height={num((y - zero).abs())}
+                    fill={color(m)}>
+                    <title>{format!("{} {measure}: {}", row.category(), format_value(value))}</title>
+                </rect>
+            }
+        })
+    });
+
+    html! {
+        <>
+            <svg class="chart" viewBox={format!("0 0 {WIDTH} {HEIGHT}")}>
+                {axes(&scale)}
+                {for bars}
+                {categories(result, band)}
+            </svg>
+            {legend(result.measures.iter().map(String::as_str))}
+        </>
+    }
+}
+
+/// 折线图：每个聚合值一条折线，按分组顺序连接
+pub fn line(result: &AggregateResult) -> Html {
+    if result.rows.is_empty() || result.measures.is_empty() {
+        return empty();
+    }
+    let scale = Scale::new(result);
+    let band = band(result.rows.len());
+    let x = |i: usize| MARGIN + band * (i as f64 + 0.5);
+    let lines = result.measures.iter().enumerate().map(|(m, measure)| {
+        let points = result
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| format!("{},{}", num(x(i)), num(scale.y(row.value(m)))))
+            .collect::<Vec<_>>()
+            .join(" ");
+        html! {
+            <g>
+                <polyline {points} fill="none" stroke={color(m)} stroke-width="2" />
+                {for result.rows.iter().enumerate().map(|(i, row)| html! {
+                    <circle cx={num(x(i))} cy={num(scale.y(row.value(m)))} r="3" fill={color(m)}>
+                        <title>{format!("{} {measure}: {}", row.category(), format_value(row.value(m)))}</title>
+                    </circle>
+                })}
+            </g>
+        }
+    });
+
+    html! {
+        <>
+            <svg class="chart" viewBox={format!("0 0 {WIDTH} {HEIGHT}")}>
+                {axes(&scale)}
+                {for lines}
+                {categories(result, band)}
+            </svg>
+            {legend(result.measures.iter().map(String::as_str))}
+        </>
+    }
+}
+
+/// 饼图：只使用第一个聚合值，忽略非正数
+pub fn pie(result: &AggregateResult) -> Html {
+    let total = result
+        .rows
+        .iter()
+        .map(|row| row.value(0).max(0.0))
+        .sum::<f64>();
+    if total <= 0.0 {
+        return empty();
+    }
+    let (cx, cy) = (WIDTH / 2.0, HEIGHT / 2.0);
+    let r = HEIGHT / 2.0 - 8.0;
+    let point = |angle: f64| {
+        let (sin, cos) = (angle - std::f64::consts::FRAC_PI_2).sin_cos();
+        (cx + r * cos, cy + r * sin)
+    };
+    let measure = result
+        .measures
+        .first()
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    let mut start = 0.0;
+    let slices = result.rows.iter().enumerate().map(|(i, row)| {
+        let value = row.value(0).max(0.0);
+        let sweep = value / total * std::f64::consts::TAU;
+        let title = format!("{} {measure}: {} ({:.1}%)", row.category(), format_value(value), value / total * 100.0);
+        let slice = if sweep >= std::f64::consts::TAU - f64::EPSILON {
+            html! {
+                <circle cx={num(cx)} cy={num(cy)} r={num(r)} fill={color(i)}><title>{title}</title></circle>
+            }
+        } else {
+            let (x1, y1) = point(start);
+            let (x2, y2) = point(start + sweep);
+            let large = u8::from(sweep > std::f64::consts::PI);
+            let d = format!(
+                "M {} {} L {} {} A {} {} 0 {large} 1 {} {} Z",
+                num(cx), num(cy), num(x1), num(y1), num(r), num(r), num(x2), num(y2)
+            );
+            html! {
+                <path {d} fill={color(i)} stroke="white"><title>{title}</title></path>
+            }
+        };
+        start += sweep;
+        slice
+    });
+
+    html! {
+        <>
+            <svg class="chart" viewBox={format!("0 0 {WIDTH} {HEIGHT}")}>
+                {for slices}
+            </svg>
+            {legend(result.rows.iter().map(|row| row.category()))}
+        </>
+    }
+}
+
+fn empty() -> Html {
+    html! { <p class="has-text-grey has-text-centered">{t("dashboard.no_data")}</p> }
+}
+
+/// 每个分类占用的宽度
+fn band(count: usize) -> f64 {
+    (WIDTH - 2.0 * MARGIN) / count as f64
+}
+
+/// 横轴画在 0 处，纵轴标出最小值和最大值
+fn axes(scale: &Scale) -> Html {
+    let zero = num(scale.y(0.0));
+    let tick = |value: f64| {
+        html! {
+            <text x={num(MARGIN - 4.0)} y={num(scale.y(value) + 4.0)} text-anchor="end" font-size="10">
+                {format_value(value)}
+            </text>
+        }
+    };
+
+    html! {
+        <g stroke="#b5b5b5">
+            <line x1={num(MARGIN)} y1={zero.clone()} x2={num(WIDTH - MARGIN)} y2={zero} />
+            <line x1={num(MARGIN)} y1={num(MARGIN)} x2={num(MARGIN)} y2={num(HEIGHT - MARGIN)} />
+            <g stroke="none">
+                {tick(scale.max)}
+                if scale.min < 0.0 {
+                    {tick(scale.min)}
+                }
+            </g>
+        </g>
+    }
+}
+
+fn categories(result: &AggregateResult, band: f64) -> Html {
+    let step = result.rows.len().div_ceil(MAX_TICKS);
+
+    html! {
+        {for result.rows.iter().enumerate().step_by(step.max(1)).map(|(i, row)| html! {
+            <text x={num(MARGIN + band * (i as f64 + 0.5))} y={num(HEIGHT - MARGIN + 14.0)}
+                text-anchor="middle" font-size="10">
+                {truncate(&row.category(), 10)}
+            </text>
+        })}
+    }
+}
+
+fn legend<S: AsRef<str>>(names: impl Iterator<Item = S>) -> Html {
+    html! {
+        <div class="tags is-centered">
+            {for names.enumerate().map(|(i, name)| html! {
+                <span class="tag is-white">
+                    <span class="mr-1" style={format!("color: {}", color(i))}>{"\u{25a0}"}</span>
+                    {name.as_ref()}
+                </span>
+            })}
+        </div>
+    }
+}
+
+fn color(i: usize) -> &'static str {
+    PALETTE[i % PALETTE.len()]
+}
+
+/// 整数不显示小数，其余保留两位
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+fn num(value: f64) -> String {
+    format!("{value:.1}")
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        text.chars().take(max - 1).chain(['…']).collect()
+    }
+}
@@ -0,0 +1,604 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{
+    classes, html, Callback, Component, Context, Event, Html, MouseEvent, Properties, TargetCast,
+};
+
+use crate::app::api;
+use crate::app::components::chart::{self, AggregateResult};
+use crate::app::components::form::{ColumnInfo, Form};
+use crate::app::i18n::t;
+use crate::app::msg::Msg;
+
+/// 与后端 WidgetType 一致，(值, 翻译键)
+const WIDGET_TYPES: [(&str, &str); 4] = [
+    ("kpi", "dashboard.type.kpi"),
+    ("bar", "dashboard.type.bar"),
+    ("line", "dashboard.type.line"),
+    ("pie", "dashboard.type.pie"),
+];
+/// 与后端 AggregateFunc 一致，(值, 翻译键)
+const FUNCS: [(&str, &str); 5] = [
+    ("count", "dashboard.func.count"),
+    ("sum", "dashboard.func.sum"),
+    ("avg", "dashboard.func.avg"),
+    ("min", "dashboard.func.min"),
+    ("max", "dashboard.func.max"),
+];
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct Aggregate {
+    pub func: String,
+    pub column: Option<String>,
+}
+
+/// 组件的统计条件，与 `/crud/aggregate` 的请求对应
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct WidgetQuery {
+    pub group_by: Vec<String>,
+    pub aggregates: Vec<Aggregate>,
+    pub conditions: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct Widget {
+    pub widget_id: Option<u32>,
+    /// 为空时属于当前用户
+    pub role: Option<String>,
+    pub widget_type: String,
+    pub title: String,
+    pub table_name: String,
+    pub query: WidgetQuery,
+    pub widget_order: i32,
+}
+
+impl Default for Widget {
+    fn default() -> Self {
+        Widget {
+            widget_id: None,
+            role: None,
+            widget_type: WIDGET_TYPES[0].0.to_string(),
+            title: String::new(),
+            table_name: String::new(),
+            query: WidgetQuery {
+                aggregates: vec![Aggregate {
+                    func: FUNCS[0].0.to_string(),
+                    column: None,
+                }],
+                ..Default::default()
+            },
+            widget_order: 0,
+        }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct DashboardInfo {
+    /// 网关认证的当前用户，匿名时为空
+    #[serde(default)]
+    pub user_id: Option<String>,
+    pub roles: Vec<String>,
+    pub widgets: Vec<Widget>,
+}
+
+impl DashboardInfo {
+    /// 组件保存到本人或所属角色，两者都没有时只能查看
+    pub fn editable(&self) -> bool {
+        self.user_id.is_some() || !self.roles.is_empty()
+    }
+}
+
+/// 编辑器中可修改的项
+#[derive(Clone, Copy, Debug)]
+pub enum WidgetField {
+    Title,
+    Type,
+    Table,
+    GroupBy,
+    Func,
+    Column,
+    Role,
+    Order,
+}
+
+/// 首页仪表盘：显示当前用户及其角色的组件，可新增和编辑
+pub struct Dashboard {
+    info: DashboardInfo,
+    editing: Option<Widget>,
+    error: Option<String>,
+}
+
+impl Component for Dashboard {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_future(load());
+
+        Self {
+            info: DashboardInfo::default(),
+            editing: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::WidgetsLoaded(info) => {
+                match info {
+                    Ok(info) => {
+                        self.info = info;
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            Msg::WidgetEdit(widget) => {
+                self.editing = widget;
+                true
+            }
+            Msg::WidgetSaved => {
+                self.editing = None;
+                ctx.link().send_future(load());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        // 没有用户标识时只能保存到角色
+        let role = self
+            .info
+            .user_id
+            .is_none()
+            .then(|| self.info.roles.first().cloned())
+            .flatten();
+        let add = ctx.link().callback(move |_: MouseEvent| {
+            Msg::WidgetEdit(Some(Widget {
+                title: t("dashboard.untitled").to_string(),
+                role: role.clone(),
+                ..Default::default()
+            }))
+        });
+        let editable = self.info.editable();
+        let onedit = ctx.link().callback(|widget| Msg::WidgetEdit(Some(widget)));
+
+        html! {
+            <div class="block">
+                <nav class="level">
+                    <div class="level-left">
+                        <h1 class="title is-4">{t("dashboard.title")}</h1>
+                    </div>
+                    if editable {
+                        <div class="level-right">
+                            <button class="button is-primary is-small" onclick={add}>{t("dashboard.add")}</button>
+                        </div>
+                    }
+                </nav>
+                if !editable {
+                    <div class="notification is-info is-light">{t("dashboard.sign_in")}</div>
+                }
+                if let Some(e) = &self.error {
+                    <div class="notification is-danger is-light">{e}</div>
+                }
+                if let Some(widget) = &self.editing {
+                    <WidgetEditor key={widget.widget_id.map(|id| id.to_string()).unwrap_or_default()}
+                        widget={widget.clone()} roles={self.info.roles.clone()}
+                        personal={self.info.user_id.is_some()}
+                        onsaved={ctx.link().callback(|_| Msg::WidgetSaved)}
+                        oncancel={ctx.link().callback(|_| Msg::WidgetEdit(None))} />
+                }
+                if self.info.widgets.is_empty() {
+                    <p class="has-text-grey">{t("dashboard.empty")}</p>
+                }
+                <div class="columns is-multiline">
+                    {for self.info.widgets.iter().map(|widget| {
+                        let size = if widget.widget_type == "kpi" { "is-one-third" } else { "is-half" };
+                        html! {
+                            <div class={classes!("column", size)} key={widget.widget_id.unwrap_or_default()}>
+                                <WidgetCard widget={widget.clone()} {editable} onedit={onedit.clone()} />
+                            </div>
+                        }
+                    })}
+                </div>
+            </div>
+        }
+    }
+}
+
+async fn load() -> Msg {
+    Msg::WidgetsLoaded(api::get("/dashboard/widgets").await)
+}
+
+/// 对应后端的 AggregateInfo
+#[derive(Serialize)]
+struct AggregateRequest<'a> {
+    table_name: &'a str,
+    #[serde(flatten)]
+    query: &'a WidgetQuery,
+    with_label: bool,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct WidgetCardProps {
+    pub widget: Widget,
+    /// 匿名用户不显示编辑按钮
+    pub editable: bool,
+    pub onedit: Callback<Widget>,
+}
+
+/// 单个组件：按保存的统计条件查询并绘制
+pub struct WidgetCard {
+    result: Option<AggregateResult>,
+    error: Option<String>,
+}
+
+impl Component for WidgetCard {
+    type Message = Msg;
+    type Properties = WidgetCardProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self::query(ctx);
+
+        Self {
+            result: None,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::AggregateLoaded(result) => {
+                match result {
+                    Ok(result) => {
+                        self.result = Some(result);
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().widget != old_props.widget {
+            Self::query(ctx);
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let widget = props.widget.clone();
+        let onedit = props.onedit.reform(move |_: MouseEvent| widget.clone());
+
+        html! {
+            <div class="card">
+                <header class="card-header">
+                    <p class="card-header-title">
+                        {&*props.widget.title}
+                        if let Some(role) = &props.widget.role {
+                            <span class="tag is-light ml-2">{role}</span>
+                        }
+                    </p>
+                    if props.editable {
+                        <button class="card-header-icon button is-white is-small" onclick={onedit}>
+                            {t("dashboard.edit")}
+                        </button>
+                    }
+                </header>
+                <div class="card-content">
+                    if let Some(e) = &self.error {
+                        <div class="notification is-danger is-light">{e}</div>
+                    }
+                    {self.result.as_ref().map(|result| self.chart(&props.widget, result)).unwrap_or_default()}
+                </div>
+            </div>
+        }
+    }
+}
+
+impl WidgetCard {
+    fn query(ctx: &Context<Self>) {
+        let widget = ctx.props().widget.clone();
+        ctx.link().send_future(async move {
+            let req = AggregateRequest {
+                table_name: &widget.table_name,
+                query: &widget.query,
+                with_label: true,
+            };
+            Msg::AggregateLoaded(api::post("/crud/aggregate", &req).await)
+        });
+    }
+
+    fn chart(&self, widget: &Widget, result: &AggregateResult) -> Html {
+        html! {
+            <>
+                {match widget.widget_type.as_str() {
+                    "bar" => chart::bar(result),
+                    "line" => chart::line(result),
+                    "pie" => chart::pie(result),
+                    _ => chart::kpi(result),
+                }}
+                if result.truncated {
+                    <p class="help">{t("dashboard.truncated")}</p>
+                }
+            </>
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct WidgetEditorProps {
+    pub widget: Widget,
+    /// 当前用户的角色，组件可以保存到其中之一
+    pub roles: Vec<String>,
+    /// 有用户标识，可以保存到本人
+    pub personal: bool,
+    pub onsaved: Callback<()>,
+    pub oncancel: Callback<()>,
+}
+
+/// 组件编辑器：选择表、分组列、聚合方式和过滤条件
+pub struct WidgetEditor {
+    widget: Widget,
+    /// 所选表的 r 列
+    columns: Vec<ColumnInfo>,
+    busy: bool,
+    error: Option<String>,
+}
+
+impl Component for WidgetEditor {
+    type Message = Msg;
+    type Properties = WidgetEditorProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let widget = ctx.props().widget.clone();
+        load_columns(ctx, &widget.table_name);
+
+        Self {
+            widget,
+            columns: Vec::new(),
+            busy: false,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ColumnsLoaded(columns) => {
+                self.columns = columns;
+                true
+            }
+            Msg::WidgetInput(field, value) => {
+                self.input(ctx, field, value);
+                true
+            }
+            Msg::Search(conditions) => {
+                self.widget.query.conditions = conditions;
+                true
+            }
+            Msg::WidgetSave => {
+                let widget = self.widget.clone();
+                self.busy = true;
+                ctx.link().send_future(async move {
+                    let res = api::post::<_, u32>("/dashboard/widgets", &widget).await;
+                    Msg::Saved(res.map(u64::from))
+                });
+                true
+            }
+            Msg::WidgetDelete => {
+                let widget_id = match self.widget.widget_id {
+                    Some(widget_id) => widget_id,
+                    None => return false,
+                };
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message(t("dashboard.delete_confirm")).ok())
+                    .unwrap_or_default();
+                if !confirmed {
+                    return false;
+                }
+                self.busy = true;
+                ctx.link().send_future(async move {
+                    let path = format!("/dashboard/widgets/{widget_id}");
+                    Msg::Saved(api::delete(&path, &()).await)
+                });
+                true
+            }
+            Msg::Saved(res) => {
+                self.busy = false;
+                match res {
+                    Ok(_) => ctx.props().onsaved.emit(()),
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let widget = &self.widget;
+        let aggregate = widget.query.aggregates.first();
+        let func = aggregate.map(|a| a.func.as_str()).unwrap_or(FUNCS[0].0);
+        let column = aggregate.and_then(|a| a.column.clone()).unwrap_or_default();
+        let group_by = widget.query.group_by.first().cloned().unwrap_or_default();
+        let role = widget.role.clone().unwrap_or_default();
+        let save = ctx.link().callback(|_: MouseEvent| Msg::WidgetSave);
+        let delete = ctx.link().callback(|_: MouseEvent| Msg::WidgetDelete);
+        let cancel = props.oncancel.reform(|_: MouseEvent| ());
+        let is_loading = self.busy.then_some("is-loading");
+        let column_options = |selected: &str| {
+            html! {
+                {for self.columns.iter().map(|c| html! {
+                    <option value={c.column_name.clone()} selected={c.column_name == selected}>{c.label()}</option>
+                })}
+            }
+        };
+
+        html! {
+            <div class="box">
+                <div class="field is-grouped is-grouped-multiline">
+                    {self.text_field(ctx, WidgetField::Title, t("dashboard.widget_title"), &widget.title)}
+                    {self.select_field(ctx, WidgetField::Type, t("dashboard.type"), html! {
+                        {for WIDGET_TYPES.iter().map(|&(k, key)| html! {
+                            <option value={k} selected={widget.widget_type == k}>{t(key)}</option>
+                        })}
+                    })}
+                    {self.text_field(ctx, WidgetField::Table, t("dashboard.table"), &widget.table_name)}
+                    {self.text_field(ctx, WidgetField::Order, t("dashboard.order"), &widget.widget_order.to_string())}
+                </div>
+                <div class="field is-grouped is-grouped-multiline">
+                    {self.select_field(ctx, WidgetField::GroupBy, t("dashboard.group_by"), html! {
+                        <>
+                            <option value="" selected={group_by.is_empty()}></option>
+                            {column_options(&group_by)}
+                        </>
+                    })}
+                    {self.select_field(ctx, WidgetField::Func, t("dashboard.func"), html! {
+                        {for FUNCS.iter().map(|&(f, key)| html! {
+                            <option value={f} selected={func == f}>{t(key)}</option>
+                        })}
+                    })}
+                    {self.select_field(ctx, WidgetField::Column, t("dashboard.column"), html! {
+                        <>
+                            <option value="" selected={column.is_empty()}></option>
+                            {column_options(&column)}
+                        </>
+                    })}
+                    {self.select_field(ctx, WidgetField::Role, t("dashboard.owner"), html! {
+                        <>
+                            if props.personal {
+                                <option value="" selected={role.is_empty()}>{t("dashboard.owner_self")}</option>
+                            }
+                            {for props.roles.iter().map(|r| html! {
+                                <option value={r.clone()} selected={*r == role}>{r}</option>
+                            })}
+                        </>
+                    })}
+                </div>
+                if !widget.table_name.is_empty() {
+                    <p class="help">{t("dashboard.filters")}</p>
+                    <Form key={widget.table_name.clone()} table_name={widget.table_name.clone()} crud_type="s"
+                        submit_text={t("dashboard.apply_filters")} grouped=true
+                        values={widget.query.conditions.clone()} onsubmit={ctx.link().callback(Msg::Search)} />
+                }
+                if let Some(e) = &self.error {
+                    <div class="notification is-danger is-light">{e}</div>
+                }
+                <div class="buttons">
+                    <button class={classes!("button", "is-primary", is_loading)} disabled={self.busy}
+                        onclick={save}>{t("dashboard.save")}</button>
+                    <button class="button" onclick={cancel}>{t("dashboard.cancel")}</button>
+                    if widget.widget_id.is_some() {
+                        <button class={classes!("button", "is-danger", "is-light", is_loading)} disabled={self.busy}
+                            onclick={delete}>{t("dashboard.delete")}</button>
+                    }
+                </div>
+            </div>
+        }
+    }
+}
+
+impl WidgetEditor {
+    fn input(&mut self, ctx: &Context<Self>, field: WidgetField, value: String) {
+        let widget = &mut self.widget;
+        let optional = (!value.is_empty()).then(|| value.clone());
+        match field {
+            WidgetField::Title => widget.title = value,
+            WidgetField::Type => widget.widget_type = value,
+            WidgetField::Table => {
+                // 换表后原有的列和条件都不再适用
+                load_columns(ctx, &value);
+                widget.table_name = value;
+                widget.query = Widget::default().query;
+                self.columns.clear();
+            }
+            WidgetField::GroupBy => widget.query.group_by = optional.into_iter().collect(),
+            WidgetField::Func | WidgetField::Column => {
+                let mut aggregate = widget
+                    .query
+                    .aggregates
+                    .first()
+                    .cloned()
+                    .unwrap_or(Aggregate {
+                        func: FUNCS[0].0.to_string(),
+                        column: None,
+                    });
+                match field {
+                    WidgetField::Func => aggregate.func = value,
+                    _ => aggregate.column = optional,
+                }
+                widget.query.aggregates = vec![aggregate];
+            }
+            WidgetField::Role => widget.role = optional,
+            WidgetField::Order => widget.widget_order = value.trim().parse().unwrap_or_default(),
+        }
+    }
+
+    fn text_field(
+        &self,
+        ctx: &Context<Self>,
+        field: WidgetField,
+        label: &str,
+        value: &str,
+    ) -> Html {
+        // 输入完成后再更新，表名变化时才加载一次列
+        let onchange = ctx.link().callback(move |e: Event| {
+            Msg::WidgetInput(field, e.target_unchecked_into::<HtmlInputElement>().value())
+        });
+
+        html! {
+            <div class="field">
+                <label class="label">{label}</label>
+                <div class="control">
+                    <input class="input" type="text" value={value.to_string()} {onchange} />
+                </div>
+            </div>
+        }
+    }
+
+    fn select_field(
+        &self,
+        ctx: &Context<Self>,
+        field: WidgetField,
+        label: &str,
+        options: Html,
+    ) -> Html {
+        let onchange = ctx.link().callback(move |e: Event| {
+            Msg::WidgetInput(
+                field,
+                e.target_unchecked_into::<HtmlSelectElement>().value(),
+            )
+        });
+
+        html! {
+            <div class="field">
+                <label class="label">{label}</label>
+                <div class="control">
+                    <div class="select">
+                        <select {onchange}>{options}</select>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
+
+fn load_columns(ctx: &Context<WidgetEditor>, table_name: &str) {
+    if table_name.is_empty() {
+        return;
+    }
+    let path = format!("/crud/columns/{table_name}/r");
+    ctx.link().send_future(async move {
+        let columns = api::get::<Vec<ColumnInfo>>(&path).await;
+        Msg::ColumnsLoaded(columns.unwrap_or_default())
+    });
+}
@@ -0,0 +1,389 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::{
+    classes, html, Callback, Component, Context, Event, Html, InputEvent, MouseEvent, Properties,
+    TargetCast,
+};
+
+use crate::app::api;
+use crate::app::i18n::t;
+use crate::app::msg::Msg;
+
+/// 对应 table_columns.COLUMN_STYLE
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ColumnStyle {
+    #[default]
+    Input,
+    Select,
+    Checkbox,
+    Radio,
+    Date,
+    Time,
+    DateTime,
+    TextArea,
+}
+
+impl From<i32> for ColumnStyle {
+    fn from(style: i32) -> Self {
+        match style {
+            2 => ColumnStyle::Select,
+            3 => ColumnStyle::Checkbox,
+            4 => ColumnStyle::Radio,
+            5 => ColumnStyle::Date,
+            6 => ColumnStyle::Time,
+            7 => ColumnStyle::DateTime,
+            8 => ColumnStyle::TextArea,
+            _ => ColumnStyle::Input,
+        }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ColumnInfo {
+    pub column_name: String,
+    pub column_must: bool,
+    pub column_style: Option<i32>,
+    pub column_type: Option<String>,
+    pub column_dict: Option<i32>,
+    pub column_desc: Option<String>,
+}
+
+impl ColumnInfo {
+    pub fn style(&self) -> ColumnStyle {
+        self.column_style.map(ColumnStyle::from).unwrap_or_default()
+    }
+
+    pub fn label(&self) -> &str {
+        self.column_desc.as_deref().unwrap_or(&self.column_name)
+    }
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct DictKey {
+    pub key_order: i32,
+    pub key: String,
+    pub value: String,
+    pub desc: Option<String>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FormFieldProps {
+    pub column: ColumnInfo,
+    #[prop_or_default]
+    pub value: String,
+    #[prop_or_default]
+    pub invalid: bool,
+    /// (列名, 值)
+    pub onchange: Callback<(String, String)>,
+}
+
+/// 按 COLUMN_STYLE 渲染单个 Bulma 表单控件
+pub struct FormField {
+    options: Vec<DictKey>,
+}
+
+impl Component for FormField {
+    type Message = Msg;
+    type Properties = FormFieldProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let column = &ctx.props().column;
+        let uses_dict = matches!(
+            column.style(),
+            ColumnStyle::Select | ColumnStyle::Checkbox | ColumnStyle::Radio
+        );
+        if let Some(dict_id) = column.column_dict.filter(|_| uses_dict) {
+            ctx.link().send_future(async move {
+                let keys = api::get::<Vec<DictKey>>(&format!("/dict/{dict_id}")).await;
+                Msg::DictLoaded(keys.unwrap_or_default())
+            });
+        }
+
+        Self {
+            options: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let props = ctx.props();
+        match msg {
+            Msg::DictLoaded(options) => {
+                self.options = options;
+                true
+            }
+            Msg::FieldInput(value) => {
+                props
+                    .onchange
+                    .emit((props.column.column_name.clone(), value));
+                false
+            }
+            Msg::FieldToggle(key) => {
+                // 多选框的值以逗号分隔保存
+                let mut keys = props
+                    .value
+                    .split(',')
+                    .filter(|k| !k.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>();
+                match keys.iter().position(|k| *k == key) {
+                    Some(i) => {
+                        keys.remove(i);
+                    }
+                    None => keys.push(key),
+                }
+                props
+                    .onchange
+                    .emit((props.column.column_name.clone(), keys.join(",")));
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let column = &props.column;
+        let is_danger = props.invalid.then_some("is-danger");
+
+        html! {
+            <div class="field">
+                <label class="label">
+                    {column.label()}
+                    if column.column_must {
+                        <span class="has-text-danger">{" *"}</span>
+                    }
+                </label>
+                <div class="control">
+                    {self.control(ctx)}
+                </div>
+                if props.invalid {
+                    <p class={classes!("help", is_danger)}>{t("form.required").replace("{}", column.label())}</p>
+                }
+            </div>
+        }
+    }
+}
+
+impl FormField {
+    fn control(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let column = &props.column;
+        let value = props.value.clone();
+        let is_danger = props.invalid.then_some("is-danger");
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            Msg::FieldInput(e.target_unchecked_into::<HtmlInputElement>().value())
+        });
+
+        match column.style() {
+            ColumnStyle::Input => html! {
+                <input class={classes!("input", is_danger)} type="text" {value} {oninput}
+                    required={column.column_must} />
+            },
+            ColumnStyle::Select => {
+                let onchange = ctx.link().callback(|e: Event| {
+                    Msg::FieldInput(e.target_unchecked_into::<HtmlSelectElement>().value())
+                });
+                html! {
+                    <div class={classes!("select", is_danger)}>
+                        <select {onchange} required={column.column_must}>
+                            <option value="" selected={value.is_empty()}></option>
+                            {for self.options.iter().map(|o| html! {
+                                <option value={o.key.clone()} selected={o.key == value}>{&*o.value}</option>
+                            })}
+                        </select>
+                    </div>
+                }
+            }
+            ColumnStyle::Checkbox if self.options.is_empty() => {
+                // 没有字典的多选框视为 BOOLEAN 开关
+                let checked = value == "true";
+                let onclick = ctx
+                    .link()
+                    .callback(move |_: MouseEvent| Msg::FieldInput((!checked).to_string()));
+                html! {
+                    <label class="checkbox">
+                        <input type="checkbox" {checked} {onclick} />
+                    </label>
+                }
+            }
+            ColumnStyle::Checkbox => html! {
+                <>
+                {for self.options.iter().map(|o| {
+                    let key = o.key.clone();
+                    let checked = value.split(',').any(|k| k == key);
+                    let onclick = ctx.link().callback(move |_: MouseEvent| Msg::FieldToggle(key.clone()));
+                    html! {
+                        <label class="checkbox mr-3">
+                            <input type="checkbox" {checked} {onclick} />
+                            {" "}{&*o.value}
+                        </label>
+                    }
+                })}
+                </>
+            },
+            ColumnStyle::Radio => html! {
+                <>
+                {for self.options.iter().map(|o| {
+                    let key = o.key.clone();
+                    let checked = o.key == value;
+                    let onclick = ctx.link().callback(move |_: MouseEvent| Msg::FieldInput(key.clone()));
+                    html! {
+                        <label class="radio">
+                            <input type="radio" name={column.column_name.clone()} {checked} {onclick} />
+                            {" "}{&*o.value}
+                        </label>
+                    }
+                })}
+                </>
+            },
+            ColumnStyle::Date => html! {
+                <input class={classes!("input", is_danger)} type="date" {value} {oninput}
+                    required={column.column_must} />
+            },
+            ColumnStyle::Time => html! {
+                <input class={classes!("input", is_danger)} type="time" step="1" {value} {oninput}
+                    required={column.column_must} />
+            },
+            ColumnStyle::DateTime => {
+                // datetime-local 使用 `T` 分隔日期和时间，后端按 `%F %T` 解析
+                let value = value.replacen(' ', "T", 1);
+                let oninput = ctx.link().callback(|e: InputEvent| {
+                    let value = e.target_unchecked_into::<HtmlInputElement>().value();
+                    Msg::FieldInput(value.replacen('T', " ", 1))
+                });
+                html! {
+                    <input class={classes!("input", is_danger)} type="datetime-local" step="1"
+                        {value} {oninput} required={column.column_must} />
+                }
+            }
+            ColumnStyle::TextArea => {
+                let oninput = ctx.link().callback(|e: InputEvent| {
+                    Msg::FieldInput(e.target_unchecked_into::<HtmlTextAreaElement>().value())
+                });
+                html! {
+                    <textarea class={classes!("textarea", is_danger)} {value} {oninput}
+                        required={column.column_must} />
+                }
+            }
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct FormProps {
+    pub table_name: String,
+    /// crud_info.CRUD_TYPE，决定渲染哪些列
+    pub crud_type: String,
+    #[prop_or_else(|| t("form.submit").to_string())]
+    pub submit_text: String,
+    /// 各列横向排列，用于查询条件
+    #[prop_or_default]
+    pub grouped: bool,
+    /// 不渲染的列，如从表中由主表记录决定的外键
+    #[prop_or_default]
+    pub hidden: Vec<String>,
+    /// 各列的初始值，如编辑已保存的查询条件
+    #[prop_or_default]
+    pub values: HashMap<String, String>,
+    pub onsubmit: Callback<HashMap<String, String>>,
+}
+
+/// 根据 crud_info 配置生成的表单，提交前校验必填项
+pub struct Form {
+    columns: Vec<ColumnInfo>,
+    values: HashMap<String, String>,
+    missing: HashSet<String>,
+}
+
+impl Component for Form {
+    type Message = Msg;
+    type Properties = FormProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = ctx.props();
+        let path = format!("/crud/columns/{}/{}", props.table_name, props.crud_type);
+        ctx.link().send_future(async move {
+            let columns = api::get::<Vec<ColumnInfo>>(&path).await;
+            Msg::ColumnsLoaded(columns.unwrap_or_default())
+        });
+
+        Self {
+            columns: Vec::new(),
+            values: props.values.clone(),
+            missing: HashSet::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ColumnsLoaded(columns) => {
+                let hidden = &ctx.props().hidden;
+                self.columns = columns
+                    .into_iter()
+                    .filter(|c| !hidden.contains(&c.column_name))
+                    .collect();
+                true
+            }
+            Msg::FormChanged(column_name, value) => {
+                if !value.is_empty() {
+                    self.missing.remove(&column_name);
+                }
+                self.values.insert(column_name, value);
+                true
+            }
+            Msg::FormSubmit => {
+                self.missing = self
+                    .columns
+                    .iter()
+                    .filter(|c| c.column_must)
+                    .filter(|c| self.values.get(&c.column_name).is_none_or(String::is_empty))
+                    .map(|c| c.column_name.clone())
+                    .collect();
+
+                if self.missing.is_empty() {
+                    let values = self
+                        .values
+                        .iter()
+                        .filter(|(_, v)| !v.is_empty())
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect();
+                    ctx.props().onsubmit.emit(values);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onchange = ctx
+            .link()
+            .callback(|(column_name, value)| Msg::FormChanged(column_name, value));
+        let onclick = ctx.link().callback(|_: MouseEvent| Msg::FormSubmit);
+        let fields = self.columns.iter().map(|column| {
+            html! {
+                <FormField column={column.clone()}
+                    value={self.values.get(&column.column_name).cloned().unwrap_or_default()}
+                    invalid={self.missing.contains(&column.column_name)}
+                    onchange={onchange.clone()} />
+            }
+        });
+
+        html! {
+            <div class={classes!(props.grouped.then_some("field is-grouped is-grouped-multiline"))}>
+                {for fields}
+                <div class="field">
+                    if props.grouped {
+                        <label class="label">{"\u{00a0}"}</label>
+                    }
+                    <div class="control">
+                        <button class="button is-primary" {onclick}>{&*props.submit_text}</button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
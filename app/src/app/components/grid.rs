@@ -0,0 +1,802 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web_sys::{Element, HtmlInputElement};
+use yew::{
+    classes, html, Callback, Component, Context, DragEvent, Event, Html, MouseEvent, PointerEvent,
+    Properties, TargetCast,
+};
+
+use crate::app::api;
+use crate::app::components::form::{ColumnInfo, Form};
+use crate::app::components::import::Import;
+use crate::app::components::layout::{ColumnChooser, GridLayout, DEFAULT_WIDTH, MIN_WIDTH};
+use crate::app::components::view::{SortKey, ViewBar, ViewSettings};
+use crate::app::i18n::t;
+use crate::app::msg::Msg;
+
+/// 没有视图设置每页行数时的默认值
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// 查询结果的一行，配置了字典或主表标签的列另有 `<列名>_label`
+pub type Row = HashMap<String, Option<String>>;
+
+/// 对应后端 table_relations 中的一条主从关联
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct Relation {
+    pub table_name: String,
+    pub column_name: String,
+    pub parent_table: String,
+    pub parent_column: String,
+    pub label_column: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct Relations {
+    children: Vec<Relation>,
+}
+
+/// 从表所属的主表记录
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Parent {
+    pub relation: Relation,
+    /// 主表关联列的值
+    pub value: String,
+}
+
+/// 对应后端的 ParentKey
+#[derive(Serialize)]
+struct ParentKey<'a> {
+    table_name: &'a str,
+    value: &'a str,
+}
+
+impl<'a> From<&'a Parent> for ParentKey<'a> {
+    fn from(parent: &'a Parent) -> Self {
+        ParentKey {
+            table_name: &parent.relation.parent_table,
+            value: &parent.value,
+        }
+    }
+}
+
+/// 对应后端的 CRUDInfo
+#[derive(Serialize)]
+struct CrudRequest<'a> {
+    table_name: &'a str,
+    columns: HashMap<String, String>,
+    conditions: &'a HashMap<String, String>,
+    with_label: bool,
+    parent: Option<ParentKey<'a>>,
+    sort: &'a [SortKey],
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GridProps {
+    pub table_name: String,
+    /// 作为从表显示时所属的主表记录，查询、新增和删除都限定在该记录下
+    #[prop_or_default]
+    pub parent: Option<Parent>,
+    /// 菜单项的页面编号，用于选择该菜单项的默认视图
+    #[prop_or_default]
+    pub page_id: Option<i32>,
+}
+
+/// 通用表格：按 s 列生成查询条件，按 r 列展示结果，可导出全部结果；
+/// 选中一行时在下方显示其从表；主表可以保存和切换视图；
+/// 列的顺序、显示、宽度和固定列数按用户保存在服务端
+pub struct Grid {
+    columns: Vec<ColumnInfo>,
+    /// 当前页的行，由服务端分页
+    rows: Vec<Row>,
+    /// 符合查询条件的总行数
+    total: usize,
+    /// 最近一次查询的条件，导出时沿用
+    conditions: HashMap<String, String>,
+    exporting: bool,
+    error: Option<String>,
+    /// 引用本表的从表
+    children: Vec<Relation>,
+    selected: Option<usize>,
+    /// 作为从表时按 d 列删除单行
+    delete_columns: Vec<ColumnInfo>,
+    sort: Vec<SortKey>,
+    /// 当前用户的列布局
+    layout: GridLayout,
+    /// 布局是否保存到后端，没有用户标识时只在本次打开时有效
+    layout_saved: bool,
+    /// 视图中显示的列及顺序，为空时按布局显示
+    view_columns: Vec<String>,
+    /// 正在拖动的表头
+    dragging: Option<String>,
+    /// 正在调整宽度的列：(列名, 开始时的指针横坐标, 开始时的宽度)
+    resizing: Option<(String, i32, u32)>,
+    /// 从 0 开始
+    page: usize,
+    page_size: usize,
+    /// 应用视图时重建查询表单，使其显示视图的条件
+    form_key: u32,
+}
+
+impl Component for Grid {
+    type Message = Msg;
+    type Properties = GridProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let props = ctx.props();
+        let path = format!("/crud/columns/{}/r", props.table_name);
+        ctx.link().send_future(async move {
+            let columns = api::get::<Vec<ColumnInfo>>(&path).await;
+            Msg::ColumnsLoaded(columns.unwrap_or_default())
+        });
+        let path = format!("/crud/relations/{}", props.table_name);
+        ctx.link().send_future(async move {
+            let relations = api::get::<Relations>(&path).await;
+            Msg::RelationsLoaded(relations.unwrap_or_default().children)
+        });
+        let path = format!("/layout/{}", props.table_name);
+        ctx.link()
+            .send_future(async move { Msg::LayoutLoaded(api::get::<GridLayout>(&path).await) });
+        if props.parent.is_some() {
+            let path = format!("/crud/columns/{}/d", props.table_name);
+            ctx.link().send_future(async move {
+                let columns = api::get::<Vec<ColumnInfo>>(&path).await;
+                Msg::DeleteColumnsLoaded(columns.unwrap_or_default())
+            });
+            ctx.link().send_message(Msg::Search(HashMap::new()));
+        }
+
+        Self {
+            columns: Vec::new(),
+            rows: Vec::new(),
+            total: 0,
+            conditions: HashMap::new(),
+            exporting: false,
+            error: None,
+            children: Vec::new(),
+            selected: None,
+            delete_columns: Vec::new(),
+            sort: Vec::new(),
+            layout: GridLayout::default(),
+            layout_saved: false,
+            view_columns: Vec::new(),
+            dragging: None,
+            resizing: None,
+            page: 0,
+            page_size: DEFAULT_PAGE_SIZE,
+            form_key: 0,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let table_name = ctx.props().table_name.clone();
+        let parent = ctx.props().parent.clone();
+        match msg {
+            Msg::ColumnsLoaded(columns) => {
+                self.columns = columns;
+                true
+            }
+            Msg::RelationsLoaded(children) => {
+                self.children = children;
+                true
+            }
+            Msg::DeleteColumnsLoaded(columns) => {
+                self.delete_columns = columns;
+                true
+            }
+            Msg::Search(conditions) => {
+                self.conditions = conditions.clone();
+                ctx.link().send_future(async move {
+                    let req = CrudRequest {
+                        table_name: &table_name,
+                        columns: HashMap::new(),
+                        conditions: &conditions,
+                        with_label: false,
+                        parent: parent.as_ref().map(Into::into),
+                        sort: &[],
+                        offset: None,
+                        limit: None,
+                    };
+                    Msg::CountLoaded(api::post("/crud/count", &req).await)
+                });
+                self.load_rows(ctx);
+                false
+            }
+            Msg::RowsLoaded(rows) => {
+                match rows {
+                    Ok(rows) => {
+                        self.rows = rows;
+                        self.selected = None;
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            Msg::CountLoaded(total) => {
+                match total {
+                    Ok(total) => {
+                        self.total = total as usize;
+                        // 删除或条件变化后当前页可能超出范围
+                        if self.page > self.last_page() {
+                            self.page = self.last_page();
+                            self.load_rows(ctx);
+                        }
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            Msg::RowSelected(i) => {
+                self.selected = (self.selected != Some(i)).then_some(i);
+                true
+            }
+            Msg::Create(columns) => {
+                ctx.link().send_future(async move {
+                    let req = CrudRequest {
+                        table_name: &table_name,
+                        columns,
+                        conditions: &HashMap::new(),
+                        with_label: false,
+                        parent: parent.as_ref().map(Into::into),
+                        sort: &[],
+                        offset: None,
+                        limit: None,
+                    };
+                    Msg::Saved(api::post("/crud/create", &req).await)
+                });
+                false
+            }
+            Msg::Delete(i) => {
+                let row = match self.rows.get(i) {
+                    Some(row) => row,
+                    None => return false,
+                };
+                let confirmed = web_sys::window()
+                    .and_then(|w| w.confirm_with_message(t("grid.delete_confirm")).ok())
+                    .unwrap_or_default();
+                if !confirmed {
+                    return false;
+                }
+                let conditions = self
+                    .delete_columns
+                    .iter()
+                    .filter_map(|c| {
+                        let value = row.get(&c.column_name).cloned().flatten()?;
+                        Some((c.column_name.clone(), value))
+                    })
+                    .collect::<HashMap<_, _>>();
+                // 没有条件时会删除主表记录下的全部从表记录
+                if conditions.is_empty() {
+                    return false;
+                }
+                ctx.link().send_future(async move {
+                    let req = CrudRequest {
+                        table_name: &table_name,
+                        columns: HashMap::new(),
+                        conditions: &conditions,
+                        with_label: false,
+                        parent: parent.as_ref().map(Into::into),
+                        sort: &[],
+                        offset: None,
+                        limit: None,
+                    };
+                    Msg::Saved(api::delete("/crud/delete", &req).await)
+                });
+                false
+            }
+            Msg::Saved(res) => {
+                match res {
+                    Ok(_) => ctx
+                        .link()
+                        .send_message(Msg::Search(self.conditions.clone())),
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            Msg::Export(format) => {
+                let conditions = self.conditions.clone();
+                let sort = self.sort.clone();
+                self.exporting = true;
+                ctx.link().send_future(async move {
+                    let req = CrudRequest {
+                        table_name: &table_name,
+                        columns: HashMap::new(),
+                        conditions: &conditions,
+                        with_label: true,
+                        parent: parent.as_ref().map(Into::into),
+                        sort: &sort,
+                        offset: None,
+                        limit: None,
+                    };
+                    let path = format!("/crud/export?format={format}");
+                    let filename = format!("{table_name}.{format}");
+                    Msg::Exported(api::download(&path, &req, &filename).await)
+                });
+                true
+            }
+            Msg::Exported(res) => {
+                self.exporting = false;
+                self.error = res.err();
+                true
+            }
+            Msg::ViewApplied(settings) => {
+                self.sort = settings.sort;
+                self.view_columns = settings.columns;
+                self.page_size = settings
+                    .page_size
+                    .map_or(DEFAULT_PAGE_SIZE, |size| size as usize)
+                    .max(1);
+                self.page = 0;
+                self.form_key += 1;
+                ctx.link().send_message(Msg::Search(settings.conditions));
+                true
+            }
+            Msg::Sort(column) => {
+                // 单列排序，依次切换为升序、降序、不排序
+                self.sort = match self.sort.first() {
+                    Some(key) if key.column == column && key.desc != Some(true) => vec![SortKey {
+                        column,
+                        desc: Some(true),
+                    }],
+                    Some(key) if key.column == column => Vec::new(),
+                    _ => vec![SortKey { column, desc: None }],
+                };
+                self.load_rows(ctx);
+                true
+            }
+            Msg::Page(page) => {
+                self.page = page.min(self.last_page());
+                self.load_rows(ctx);
+                true
+            }
+            Msg::PageSize(size) => {
+                self.page_size = size.max(1);
+                self.page = 0;
+                self.load_rows(ctx);
+                true
+            }
+            Msg::LayoutLoaded(layout) => {
+                match layout {
+                    Ok(layout) => {
+                        self.layout = layout;
+                        self.layout_saved = true;
+                    }
+                    Err(e) => log::warn!("{e}"),
+                }
+                true
+            }
+            Msg::LayoutSaved(res) => {
+                if let Err(e) = res {
+                    log::warn!("{e}");
+                }
+                false
+            }
+            Msg::ColumnToggle(column) => {
+                self.detach_view();
+                self.layout.toggle(&column);
+                // 至少保留一列
+                if self.visible_columns().is_empty() {
+                    self.layout.toggle(&column);
+                    return false;
+                }
+                self.save_layout(ctx);
+                true
+            }
+            Msg::ColumnFrozen(frozen) => {
+                self.layout.frozen = frozen;
+                self.save_layout(ctx);
+                true
+            }
+            Msg::LayoutReset => {
+                self.layout = GridLayout::default();
+                self.view_columns.clear();
+                if self.layout_saved {
+                    let path = format!("/layout/{table_name}");
+                    ctx.link().send_future(async move {
+                        Msg::LayoutSaved(api::delete(&path, &()).await)
+                    });
+                }
+                true
+            }
+            Msg::ColumnDragStart(column) => {
+                self.dragging = Some(column);
+                true
+            }
+            Msg::ColumnDrop(target) => {
+                let column = match self.dragging.take() {
+                    Some(column) => column,
+                    None => return false,
+                };
+                if let Some(target) = target.filter(|target| *target != column) {
+                    self.detach_view();
+                    self.layout.move_column(&self.columns, &column, &target);
+                    self.save_layout(ctx);
+                }
+                true
+            }
+            Msg::ResizeStart(column, x, width) => {
+                self.resizing = Some((column, x, width));
+                false
+            }
+            Msg::ResizeMove(x) => {
+                let (column, start_x, start_width) = match &self.resizing {
+                    Some(resizing) => resizing.clone(),
+                    None => return false,
+                };
+                let width = (start_width as i32 + x - start_x).max(MIN_WIDTH as i32);
+                self.layout.widths.insert(column, width as u32);
+                true
+            }
+            Msg::ResizeEnd => {
+                if self.resizing.take().is_some() {
+                    self.save_layout(ctx);
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onsubmit = ctx.link().callback(Msg::Search);
+        let conditions = self.conditions.clone();
+        let onimported = ctx
+            .link()
+            .callback(move |_| Msg::Search(conditions.clone()));
+        let export = |format: &'static str| {
+            ctx.link()
+                .callback(move |_: MouseEvent| Msg::Export(format))
+        };
+        let is_loading = self.exporting.then_some("is-loading");
+        // 外键由主表记录决定，不在表单中填写
+        let hidden = props
+            .parent
+            .iter()
+            .map(|p| p.relation.column_name.clone())
+            .collect::<Vec<_>>();
+        let deletable = props.parent.is_some() && !self.delete_columns.is_empty();
+        let layout = self.current_layout();
+        let columns = self.visible_columns();
+        let styles = self.column_styles(&columns);
+        // 按默认布局显示全部列时视图不保存列，之后新增的列也能显示
+        let all_columns = self.view_columns.is_empty()
+            && self.layout.columns.is_empty()
+            && self.layout.hidden.is_empty();
+        let settings = ViewSettings {
+            conditions: self.conditions.clone(),
+            sort: self.sort.clone(),
+            columns: if all_columns {
+                Vec::new()
+            } else {
+                columns.iter().map(|c| c.column_name.clone()).collect()
+            },
+            page_size: u32::try_from(self.page_size).ok(),
+        };
+
+        html! {
+            <div class="box">
+                if props.parent.is_none() {
+                    <ViewBar table_name={props.table_name.clone()} page_id={props.page_id} {settings}
+                        onapply={ctx.link().callback(Msg::ViewApplied)} />
+                }
+                <Form key={self.form_key} table_name={props.table_name.clone()} crud_type="s" submit_text={t("common.search")}
+                    grouped=true hidden={hidden.clone()} values={self.conditions.clone()} {onsubmit} />
+                if props.parent.is_some() {
+                    <Form table_name={props.table_name.clone()} crud_type="c" submit_text={t("grid.add")} grouped=true
+                        {hidden} onsubmit={ctx.link().callback(Msg::Create)} />
+                } else {
+                    <Import table_name={props.table_name.clone()} {onimported} />
+                }
+                if let Some(e) = &self.error {
+                    <div class="notification is-danger is-light">{e}</div>
+                }
+                <div class="table-container grid-container">
+                    <table class="table is-bordered is-striped is-narrow is-hoverable is-fullwidth">
+                        <thead>
+                            <tr>
+                                {for columns.iter().zip(&styles).map(|(c, style)| self.header_view(ctx, c, style))}
+                                if deletable {
+                                    <th></th>
+                                }
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {for self.rows.iter().enumerate()
+                                .map(|(i, row)| self.row_view(ctx, &columns, &styles, i, row, deletable))}
+                        </tbody>
+                    </table>
+                </div>
+                <nav class="level">
+                    <div class="level-left">
+                        <div class="level-item">
+                            <div class="buttons has-addons">
+                                <button class={classes!("button", "is-small", is_loading)} disabled={self.exporting}
+                                    onclick={export("csv")}>{t("grid.export_csv")}</button>
+                                <button class={classes!("button", "is-small", is_loading)} disabled={self.exporting}
+                                    onclick={export("xlsx")}>{t("grid.export_xlsx")}</button>
+                            </div>
+                        </div>
+                        <div class="level-item">
+                            <ColumnChooser columns={layout.ordered(&self.columns).into_iter().cloned().collect::<Vec<_>>()}
+                                layout={layout.clone()} ontoggle={ctx.link().callback(Msg::ColumnToggle)}
+                                onfrozen={ctx.link().callback(Msg::ColumnFrozen)}
+                                onreset={ctx.link().callback(|_| Msg::LayoutReset)} />
+                        </div>
+                    </div>
+                    {self.pager_view(ctx)}
+                </nav>
+                {self.detail_view()}
+            </div>
+        }
+    }
+}
+
+impl Grid {
+    /// 应用了视图时按视图的列显示，否则按用户的布局
+    fn current_layout(&self) -> GridLayout {
+        if self.view_columns.is_empty() {
+            self.layout.clone()
+        } else {
+            self.layout.with_columns(&self.columns, &self.view_columns)
+        }
+    }
+
+    fn visible_columns(&self) -> Vec<&ColumnInfo> {
+        let layout = self.current_layout();
+        layout
+            .ordered(&self.columns)
+            .into_iter()
+            .filter(|c| !layout.is_hidden(&c.column_name))
+            .collect()
+    }
+
+    /// 调整列之前把视图的列并入布局，之后按布局显示
+    fn detach_view(&mut self) {
+        if !self.view_columns.is_empty() {
+            self.layout = self.current_layout();
+            self.view_columns.clear();
+        }
+    }
+
+    fn save_layout(&self, ctx: &Context<Self>) {
+        if !self.layout_saved {
+            return;
+        }
+        let path = format!("/layout/{}", ctx.props().table_name);
+        let layout = self.layout.clone();
+        ctx.link()
+            .send_future(async move { Msg::LayoutSaved(api::put(&path, &layout).await) });
+    }
+
+    /// 各列是否固定及其样式：设置了列宽的列使用该宽度，固定列按前面固定列的宽度设置 left
+    fn column_styles(&self, columns: &[&ColumnInfo]) -> Vec<(bool, Option<String>)> {
+        let mut left = 0;
+        columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let frozen = i < self.layout.frozen as usize;
+                let width = self.layout.widths.get(&c.column_name).copied();
+                let width = width.or(frozen.then_some(DEFAULT_WIDTH));
+                let mut style = width
+                    .map(|w| format!("width: {w}px; min-width: {w}px; max-width: {w}px;"))
+                    .unwrap_or_default();
+                if let (true, Some(width)) = (frozen, width) {
+                    style += &format!(" left: {left}px;");
+                    left += width;
+                }
+                (frozen, (!style.is_empty()).then_some(style))
+            })
+            .collect()
+    }
+
+    /// 按当前条件、排序和页码读取一页
+    fn load_rows(&self, ctx: &Context<Self>) {
+        let props = ctx.props();
+        let table_name = props.table_name.clone();
+        let parent = props.parent.clone();
+        let conditions = self.conditions.clone();
+        let sort = self.sort.clone();
+        let (offset, limit) = (self.page * self.page_size, self.page_size);
+        ctx.link().send_future(async move {
+            let req = CrudRequest {
+                table_name: &table_name,
+                columns: HashMap::new(),
+                conditions: &conditions,
+                with_label: true,
+                parent: parent.as_ref().map(Into::into),
+                sort: &sort,
+                offset: Some(offset),
+                limit: Some(limit),
+            };
+            Msg::RowsLoaded(api::post::<_, Vec<Row>>("/crud/read", &req).await)
+        });
+    }
+
+    fn last_page(&self) -> usize {
+        self.total.saturating_sub(1) / self.page_size
+    }
+
+    /// 点击表头切换排序，拖动表头文字调整顺序，拖动右边缘调整列宽
+    fn header_view(
+        &self,
+        ctx: &Context<Self>,
+        column: &ColumnInfo,
+        (frozen, style): &(bool, Option<String>),
+    ) -> Html {
+        let column_name = column.column_name.clone();
+        let onclick = ctx
+            .link()
+            .callback(move |_: MouseEvent| Msg::Sort(column_name.clone()));
+        let column_name = column.column_name.clone();
+        let ondragstart = ctx.link().callback(move |e: DragEvent| {
+            // Firefox 需要设置数据才会开始拖动
+            if let Some(data) = e.data_transfer() {
+                let _ = data.set_data("text/plain", &column_name);
+            }
+            Msg::ColumnDragStart(column_name.clone())
+        });
+        let ondragend = ctx.link().callback(|_: DragEvent| Msg::ColumnDrop(None));
+        let ondragover = Callback::from(|e: DragEvent| e.prevent_default());
+        let column_name = column.column_name.clone();
+        let ondrop = ctx.link().callback(move |e: DragEvent| {
+            e.prevent_default();
+            Msg::ColumnDrop(Some(column_name.clone()))
+        });
+        let column_name = column.column_name.clone();
+        let onpointerdown = ctx.link().callback(move |e: PointerEvent| {
+            // 不触发表头的拖动，并在指针移出表头后继续接收事件
+            e.prevent_default();
+            let target = e.target_unchecked_into::<Element>();
+            let _ = target.set_pointer_capture(e.pointer_id());
+            let width = target.parent_element().map_or(0, |th| th.client_width());
+            Msg::ResizeStart(column_name.clone(), e.client_x(), width.max(0) as u32)
+        });
+        let onpointermove = ctx
+            .link()
+            .callback(|e: PointerEvent| Msg::ResizeMove(e.client_x()));
+        let onpointerup = ctx.link().callback(|_: PointerEvent| Msg::ResizeEnd);
+        // 调整列宽时不切换排序
+        let onresizerclick = Callback::from(|e: MouseEvent| e.stop_propagation());
+        let dragging = self.dragging.as_ref() == Some(&column.column_name);
+        let arrow = match self.sort.first() {
+            Some(key) if key.column == column.column_name && key.desc == Some(true) => " \u{25bc}",
+            Some(key) if key.column == column.column_name => " \u{25b2}",
+            _ => "",
+        };
+
+        html! {
+            <th class={classes!("is-clickable", frozen.then_some("is-frozen"), dragging.then_some("is-dragging"))}
+                style={style.clone()} {onclick} {ondragover} {ondrop}>
+                <span draggable="true" {ondragstart} {ondragend}>{column.label()}{arrow}</span>
+                <span class="grid-resizer" {onpointerdown} {onpointermove} {onpointerup} onclick={onresizerclick}></span>
+            </th>
+        }
+    }
+
+    fn pager_view(&self, ctx: &Context<Self>) -> Html {
+        let last = self.last_page();
+        let page = |page: usize| ctx.link().callback(move |_: MouseEvent| Msg::Page(page));
+        // 输入框在回车或失去焦点时生效，无法解析时保持原值
+        let current = self.page;
+        let ongo = ctx.link().callback(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            let page = value.trim().parse::<usize>().ok();
+            Msg::Page(page.map_or(current, |p| p.saturating_sub(1)))
+        });
+        let page_size = self.page_size;
+        let onsize = ctx.link().callback(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            Msg::PageSize(value.trim().parse().unwrap_or(page_size))
+        });
+
+        html! {
+            <div class="level-right">
+                <div class="level-item">
+                    <div class="buttons has-addons">
+                        <button class="button is-small" disabled={self.page == 0} onclick={page(0)}>{"|<-"}</button>
+                        <button class="button is-small" disabled={self.page == 0}
+                            onclick={page(self.page.saturating_sub(1))}>{"<<<"}</button>
+                        <button class="button is-small" disabled={self.page >= last}
+                            onclick={page(self.page + 1)}>{">>>"}</button>
+                        <button class="button is-small" disabled={self.page >= last} onclick={page(last)}>{"->|"}</button>
+                    </div>
+                </div>
+                <div class="level-item">
+                    <div class="field has-addons">
+                        <div class="control">
+                            <input class="input is-small" type="number" min="1" placeholder={t("page.no")}
+                                value={(self.page + 1).to_string()} onchange={ongo} />
+                        </div>
+                        <div class="control">
+                            <span class="button is-static is-small">{format!("/ {}", last + 1)}</span>
+                        </div>
+                    </div>
+                </div>
+                <div class="level-item">
+                    <div class="field has-addons">
+                        <div class="control">
+                            <input class="input is-small" type="number" min="1" placeholder={t("page.rows")}
+                                value={self.page_size.to_string()} onchange={onsize} />
+                        </div>
+                        <div class="control">
+                            <span class="button is-static is-small">{t("page.rows")}</span>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    fn row_view(
+        &self,
+        ctx: &Context<Self>,
+        columns: &[&ColumnInfo],
+        styles: &[(bool, Option<String>)],
+        i: usize,
+        row: &Row,
+        deletable: bool,
+    ) -> Html {
+        let selected = self.selected == Some(i);
+        let onclick = (!self.children.is_empty()).then(|| {
+            ctx.link()
+                .callback(move |_: MouseEvent| Msg::RowSelected(i))
+        });
+        let delete = ctx.link().callback(move |e: MouseEvent| {
+            // 删除时不切换选中行
+            e.stop_propagation();
+            Msg::Delete(i)
+        });
+
+        html! {
+            <tr class={classes!(selected.then_some("is-selected"))} {onclick}>
+                {for columns.iter().zip(styles).map(|(c, (frozen, style))| html! {
+                    <td class={classes!(frozen.then_some("is-frozen"))} style={style.clone()}>{cell(row, c)}</td>
+                })}
+                if deletable {
+                    <td>
+                        <button class="button is-small is-danger is-light" onclick={delete}>{t("grid.delete")}</button>
+                    </td>
+                }
+            </tr>
+        }
+    }
+
+    /// 选中行的各个从表，主表关联列为空时不显示
+    fn detail_view(&self) -> Html {
+        let row = match self.selected.and_then(|i| self.rows.get(i)) {
+            Some(row) => row,
+            None => return Html::default(),
+        };
+
+        html! {
+            {for self.children.iter().filter_map(|relation| {
+                let value = row.get(&relation.parent_column).cloned().flatten()?;
+                let key = format!("{}:{}", relation.table_name, value);
+                let parent = Parent { relation: relation.clone(), value };
+                Some(html! {
+                    <div class="block" key={key}>
+                        <h2 class="subtitle is-6">{&*relation.table_name}</h2>
+                        <Grid table_name={relation.table_name.clone()} parent={Some(parent)} />
+                    </div>
+                })
+            })}
+        }
+    }
+}
+
+/// 字典列和外键列显示标签，其余显示原值
+fn cell(row: &Row, column: &ColumnInfo) -> String {
+    let label = row
+        .get(&format!("{}_label", column.column_name))
+        .cloned()
+        .flatten()
+        .filter(|label| !label.is_empty());
+    label
+        .or_else(|| row.get(&column.column_name).cloned().flatten())
+        .unwrap_or_default()
+}
@@ -0,0 +1,292 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+use web_sys::{Event, File, FormData, HtmlInputElement};
+use yew::platform::time::sleep;
+use yew::{classes, html, Callback, Component, Context, Html, MouseEvent, Properties, TargetCast};
+
+use crate::app::api;
+use crate::app::i18n::t;
+use crate::app::msg::Msg;
+
+/// 查询导入进度的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 与后端导入支持的格式一致
+const ACCEPT: &str = ".csv,.xlsx,.xlsm,.xlsb,.xls,.ods";
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct RowError {
+    pub row: u64,
+    pub column: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ImportReport {
+    pub total: u64,
+    pub valid: u64,
+    pub invalid: u64,
+    pub unmapped: Vec<String>,
+    pub errors: Vec<RowError>,
+    pub job_id: Option<String>,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct JobError {
+    pub message: String,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct ImportProgress {
+    pub job_id: String,
+    pub total: u64,
+    pub processed: u64,
+    pub inserted: u64,
+    pub updated: u64,
+    pub done: bool,
+    pub error: Option<JobError>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ImportProps {
+    pub table_name: String,
+    /// 导入成功后通知表格刷新
+    pub onimported: Callback<()>,
+}
+
+/// 先校验再导入：校验通过后才能确认写入，写入时显示进度
+pub struct Import {
+    file: Option<File>,
+    upsert: bool,
+    report: Option<ImportReport>,
+    progress: Option<ImportProgress>,
+    busy: bool,
+    error: Option<String>,
+}
+
+impl Component for Import {
+    type Message = Msg;
+    type Properties = ImportProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            file: None,
+            upsert: false,
+            report: None,
+            progress: None,
+            busy: false,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ImportFile(file) => {
+                self.file = file;
+                self.report = None;
+                self.progress = None;
+                self.error = None;
+                true
+            }
+            Msg::ImportUpsert => {
+                self.upsert = !self.upsert;
+                true
+            }
+            Msg::ImportSubmit(dry_run) => {
+                let file = match &self.file {
+                    Some(file) => file.clone(),
+                    None => return false,
+                };
+                let table_name = ctx.props().table_name.clone();
+                let upsert = self.upsert;
+                self.busy = true;
+                self.progress = None;
+                self.error = None;
+                ctx.link().send_future(async move {
+                    let report = match form(&table_name, &file, dry_run, upsert) {
+                        Ok(form) => api::upload("/crud/import", form).await,
+                        Err(e) => Err(format!("{e:?}")),
+                    };
+                    Msg::ImportReported(report)
+                });
+                true
+            }
+            Msg::ImportReported(report) => {
+                match report {
+                    Ok(report) => {
+                        if let Some(job_id) = report.job_id.clone() {
+                            ctx.link().send_future(poll(job_id));
+                        } else {
+                            self.busy = false;
+                        }
+                        self.report = Some(report);
+                    }
+                    Err(e) => {
+                        self.busy = false;
+                        self.error = Some(e);
+                    }
+                }
+                true
+            }
+            Msg::ImportProgressed(progress) => {
+                match progress {
+                    Ok(progress) if !progress.done => {
+                        ctx.link().send_future(poll(progress.job_id.clone()));
+                        self.progress = Some(progress);
+                    }
+                    Ok(progress) => {
+                        self.busy = false;
+                        self.error = progress.error.as_ref().map(|e| e.message.clone());
+                        if self.error.is_none() {
+                            // 已导入的文件不允许再次确认
+                            self.report = None;
+                            ctx.props().onimported.emit(());
+                        }
+                        self.progress = Some(progress);
+                    }
+                    Err(e) => {
+                        self.busy = false;
+                        self.error = Some(e);
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onchange = ctx.link().callback(|e: Event| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            Msg::ImportFile(input.files().and_then(|files| files.get(0)))
+        });
+        let check = ctx.link().callback(|_: MouseEvent| Msg::ImportSubmit(true));
+        let confirm = ctx
+            .link()
+            .callback(|_: MouseEvent| Msg::ImportSubmit(false));
+        let toggle = ctx.link().callback(|_: MouseEvent| Msg::ImportUpsert);
+        let is_loading = self.busy.then_some("is-loading");
+        let confirmable = self
+            .report
+            .as_ref()
+            .is_some_and(|r| r.invalid == 0 && r.valid > 0 && r.job_id.is_none());
+
+        html! {
+            <div class="block">
+                <div class="field is-grouped is-grouped-multiline">
+                    <div class="control">
+                        <div class="file is-small has-name">
+                            <label class="file-label">
+                                <input class="file-input" type="file" accept={ACCEPT} {onchange} />
+                                <span class="file-cta">
+                                    <span class="file-label">{t("import.file")}</span>
+                                </span>
+                                <span class="file-name">
+                                    {self.file.as_ref().map(File::name).unwrap_or_default()}
+                                </span>
+                            </label>
+                        </div>
+                    </div>
+                    <div class="control">
+                        <label class="checkbox is-size-7">
+                            <input type="checkbox" checked={self.upsert} onclick={toggle} />
+                            {" "}{t("import.upsert")}
+                        </label>
+                    </div>
+                    <div class="control">
+                        <div class="buttons has-addons">
+                            <button class={classes!("button", "is-small", is_loading)}
+                                disabled={self.file.is_none() || self.busy} onclick={check}>{t("import.check")}</button>
+                            <button class={classes!("button", "is-small", "is-primary", is_loading)}
+                                disabled={!confirmable || self.busy} onclick={confirm}>{t("import.confirm")}</button>
+                        </div>
+                    </div>
+                </div>
+                if let Some(e) = &self.error {
+                    <div class="notification is-danger is-light">{e}</div>
+                }
+                {self.report.as_ref().map(report_view).unwrap_or_default()}
+                {self.progress.as_ref().map(progress_view).unwrap_or_default()}
+            </div>
+        }
+    }
+}
+
+fn form(table_name: &str, file: &File, dry_run: bool, upsert: bool) -> Result<FormData, JsValue> {
+    let form = FormData::new()?;
+    form.append_with_str("table_name", table_name)?;
+    form.append_with_blob_and_filename("file", file, &file.name())?;
+    form.append_with_str("dry_run", &dry_run.to_string())?;
+    form.append_with_str("upsert", &upsert.to_string())?;
+    Ok(form)
+}
+
+async fn poll(job_id: String) -> Msg {
+    sleep(POLL_INTERVAL).await;
+    Msg::ImportProgressed(api::get(&format!("/crud/import/{job_id}")).await)
+}
+
+fn report_view(report: &ImportReport) -> Html {
+    let summary = t("import.summary")
+        .replace("{total}", &report.total.to_string())
+        .replace("{valid}", &report.valid.to_string())
+        .replace("{invalid}", &report.invalid.to_string());
+    let color = if report.invalid > 0 {
+        "is-warning"
+    } else {
+        "is-info"
+    };
+
+    html! {
+        <div class={classes!("notification", "is-light", color)}>
+            <p>{summary}</p>
+            if !report.unmapped.is_empty() {
+                <p>{t("import.unmapped")}{report.unmapped.join(", ")}</p>
+            }
+            if !report.errors.is_empty() {
+                <div class="table-container">
+                    <table class="table is-narrow is-fullwidth">
+                        <thead>
+                            <tr>
+                                <th>{t("import.row")}</th>
+                                <th>{t("import.column")}</th>
+                                <th>{t("import.message")}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {for report.errors.iter().map(|e| html! {
+                                <tr>
+                                    <td>{e.row}</td>
+                                    <td>{&*e.column}</td>
+                                    <td>{&*e.message}</td>
+                                </tr>
+                            })}
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </div>
+    }
+}
+
+fn progress_view(progress: &ImportProgress) -> Html {
+    let done = progress.done && progress.error.is_none();
+    let text = if done {
+        t("import.done")
+            .replace("{inserted}", &progress.inserted.to_string())
+            .replace("{updated}", &progress.updated.to_string())
+    } else {
+        format!("{} / {}", progress.processed, progress.total)
+    };
+
+    html! {
+        <div class="block">
+            <progress class="progress is-small is-primary" value={progress.processed.to_string()}
+                max={progress.total.to_string()} />
+            <p class="is-size-7">{text}</p>
+        </div>
+    }
+}
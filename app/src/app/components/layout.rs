@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::{
+    classes, html, Callback, Component, Context, Event, Html, MouseEvent, Properties, TargetCast,
+};
+
+use crate::app::components::form::ColumnInfo;
+use crate::app::i18n::t;
+use crate::app::msg::Msg;
+
+/// 拖动调整列宽时的最小宽度
+pub const MIN_WIDTH: u32 = 40;
+/// 固定列没有设置列宽时使用的宽度，固定列需要确定的宽度来计算后面固定列的位置
+pub const DEFAULT_WIDTH: u32 = 120;
+
+/// 对应后端的 GridLayout
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct GridLayout {
+    /// 列的顺序，未列出的列按配置顺序排在后面
+    pub columns: Vec<String>,
+    pub hidden: Vec<String>,
+    /// 列宽，单位为像素
+    pub widths: HashMap<String, u32>,
+    /// 固定在左侧的列数
+    pub frozen: u32,
+}
+
+impl GridLayout {
+    /// 按布局排序的全部列，包括隐藏的列
+    pub fn ordered<'a>(&self, columns: &'a [ColumnInfo]) -> Vec<&'a ColumnInfo> {
+        let mut ordered = self
+            .columns
+            .iter()
+            .filter_map(|name| columns.iter().find(|c| c.column_name == *name))
+            .collect::<Vec<_>>();
+        ordered.extend(
+            columns
+                .iter()
+                .filter(|c| !self.columns.contains(&c.column_name)),
+        );
+        ordered
+    }
+
+    pub fn is_hidden(&self, column_name: &str) -> bool {
+        self.hidden.iter().any(|name| name == column_name)
+    }
+
+    /// 以视图的列代替布局的顺序和可见性，列宽和固定列数不变
+    pub fn with_columns(&self, columns: &[ColumnInfo], visible: &[String]) -> Self {
+        GridLayout {
+            columns: visible.to_vec(),
+            hidden: columns
+                .iter()
+                .filter(|c| !visible.contains(&c.column_name))
+                .map(|c| c.column_name.clone())
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    pub fn toggle(&mut self, column_name: &str) {
+        if self.is_hidden(column_name) {
+            self.hidden.retain(|name| name != column_name);
+        } else {
+            self.hidden.push(column_name.to_string());
+        }
+    }
+
+    /// 把 column_name 移到 target 所在的位置
+    pub fn move_column(&mut self, columns: &[ColumnInfo], column_name: &str, target: &str) {
+        let mut order = self
+            .ordered(columns)
+            .into_iter()
+            .map(|c| c.column_name.clone())
+            .collect::<Vec<_>>();
+        let from = order.iter().position(|name| name == column_name);
+        let to = order.iter().position(|name| name == target);
+        if let (Some(from), Some(to)) = (from, to) {
+            let name = order.remove(from);
+            order.insert(to, name);
+            self.columns = order;
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ColumnChooserProps {
+    /// 按布局排序的全部列
+    pub columns: Vec<ColumnInfo>,
+    pub layout: GridLayout,
+    pub ontoggle: Callback<String>,
+    pub onfrozen: Callback<u32>,
+    pub onreset: Callback<()>,
+}
+
+/// 列选择下拉框：显示或隐藏列、设置固定列数、恢复默认布局
+pub struct ColumnChooser {
+    open: bool,
+}
+
+impl Component for ColumnChooser {
+    type Message = Msg;
+    type Properties = ColumnChooserProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { open: false }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ChooserToggle => {
+                self.open = !self.open;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let toggle = ctx.link().callback(|_: MouseEvent| Msg::ChooserToggle);
+        let frozen = props.layout.frozen;
+        let onfrozen = props.onfrozen.reform(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            value.trim().parse().unwrap_or(frozen)
+        });
+        let onreset = props.onreset.reform(|_: MouseEvent| ());
+
+        html! {
+            <div class={classes!("dropdown", self.open.then_some("is-active"))}>
+                <div class="dropdown-trigger">
+                    <button class="button is-small" onclick={toggle}>{t("grid.columns")}</button>
+                </div>
+                <div class="dropdown-menu">
+                    <div class="dropdown-content">
+                        {for props.columns.iter().map(|c| {
+                            let column_name = c.column_name.clone();
+                            let onclick = props.ontoggle.reform(move |_: MouseEvent| column_name.clone());
+                            html! {
+                                <label class="dropdown-item checkbox">
+                                    <input class="mr-1" type="checkbox" checked={!props.layout.is_hidden(&c.column_name)}
+                                        {onclick} />
+                                    {c.label()}
+                                </label>
+                            }
+                        })}
+                        <hr class="dropdown-divider" />
+                        <div class="dropdown-item">
+                            <div class="field has-addons">
+                                <div class="control">
+                                    <span class="button is-static is-small">{t("grid.frozen")}</span>
+                                </div>
+                                <div class="control">
+                                    <input class="input is-small" type="number" min="0"
+                                        value={frozen.to_string()} onchange={onfrozen} />
+                                </div>
+                            </div>
+                        </div>
+                        <div class="dropdown-item">
+                            <button class="button is-small is-light" onclick={onreset}>{t("grid.reset_layout")}</button>
+                        </div>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use yew::{html, Component, Context, Html};
 
+use crate::app::i18n::t;
 use crate::app::msg::Msg;
 
 pub struct Login;
@@ -20,20 +21,20 @@ impl Component for Login {
         html! {
         <div class="box">
           <div class="field">
-            <label class="label" for="account">{"账号"}</label>
+            <label class="label" for="account">{t("login.account")}</label>
             <div class="control">
               <input id="account" class="input" />
             </div>
           </div>
 
           <div class="field">
-            <label class="label">{"密码"}</label>
+            <label class="label">{t("login.password")}</label>
             <div class="control">
               <input class="input" type="password" placeholder="********" />
             </div>
           </div>
 
-          <button class="button is-primary">{"登录/注册"}</button>
+          <button class="button is-primary">{t("login.submit")}</button>
         </div>
         }
     }
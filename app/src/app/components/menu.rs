@@ -1,12 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 use indextree::{Arena, NodeId};
-use yew::{classes, html, html::Scope, Component, Context, Html, MouseEvent};
+use serde::Deserialize;
+use web_sys::HtmlInputElement;
+use yew::{
+    classes, html, html::Scope, Component, Context, Html, InputEvent, KeyboardEvent, MouseEvent,
+    TargetCast,
+};
 use yew_router::prelude::RouterScopeExt;
 
+use crate::app::api;
 use crate::app::components::FnRoute;
 use crate::app::context::{ContextExt, Module};
+use crate::app::i18n::t;
 use crate::app::msg::Msg;
 
 #[derive(Default, PartialEq, Eq, Clone, Debug)]
@@ -45,7 +52,17 @@ pub struct MenuNode {
     pub func_id: String,
 }
 
+/// 后端菜单检索的一条结果，只用到菜单编号
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct MenuHit {
+    pub menu_id: u32,
+}
+
 impl MenuNode {
+    fn matches(&self, query: &str) -> bool {
+        !query.is_empty() && self.text.to_lowercase().contains(query)
+    }
+
     fn new(menu_type: &str, id: u32, parent_id: u32, text: String, func_id: String) -> Self {
         let menu_type = MenuType::from_str(menu_type).unwrap_or_default();
         MenuNode {
@@ -64,6 +81,74 @@ pub struct Menu {
     nodes: Arena<MenuNode>,
     node_map: HashMap<u32, NodeId>,
     activated: u32,
+    // 小写后的搜索词
+    query: String,
+    /// 后端检索到的菜单编号，与本地的文本匹配合并
+    hits: HashSet<u32>,
+    /// 开始搜索前各节点的展开状态，清空搜索词时恢复
+    folds: Option<HashMap<u32, bool>>,
+}
+
+impl Menu {
+    fn is_match(&self, node: &MenuNode) -> bool {
+        node.matches(&self.query) || self.hits.contains(&node.id)
+    }
+
+    /// 展开所有匹配节点的上级 Fold/Label
+    fn expand_matches(&mut self) {
+        let matched = self
+            .node_map
+            .values()
+            .filter(|node_id| {
+                self.nodes
+                    .get(**node_id)
+                    .is_some_and(|node| self.is_match(node.get()))
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        for node_id in matched {
+            let ancestors = node_id.ancestors(&self.nodes).skip(1).collect::<Vec<_>>();
+            for ancestor in ancestors {
+                if let Some(node) = self.nodes.get_mut(ancestor) {
+                    node.get_mut().expanded = true;
+                }
+            }
+        }
+    }
+
+    /// 按菜单显示顺序找到第一个匹配的 Item
+    fn first_match(&self) -> Option<u32> {
+        let root = self.node_map.get(&0)?;
+        root.descendants(&self.nodes)
+            .filter_map(|node_id| self.nodes.get(node_id).map(|node| node.get()))
+            .find(|node| node.menu_type == MenuType::Item && self.is_match(node))
+            .map(|node| node.id)
+    }
+
+    fn save_folds(&mut self) {
+        if self.folds.is_none() {
+            let folds = self
+                .nodes
+                .iter()
+                .map(|node| (node.get().id, node.get().expanded))
+                .collect();
+            self.folds = Some(folds);
+        }
+    }
+
+    fn restore_folds(&mut self) {
+        let folds = match self.folds.take() {
+            Some(folds) => folds,
+            None => return,
+        };
+        for node in self.nodes.iter_mut() {
+            let node = node.get_mut();
+            if let Some(expanded) = folds.get(&node.id) {
+                node.expanded = *expanded;
+            }
+        }
+    }
 }
 
 impl Component for Menu {
@@ -129,6 +214,9 @@ impl Component for Menu {
             nodes,
             node_map,
             activated: 0,
+            query: String::new(),
+            hits: HashSet::new(),
+            folds: None,
         }
     }
 
@@ -165,6 +253,48 @@ impl Component for Menu {
 
                 true
             }
+            Msg::MenuSearch(query) => {
+                let query = query.trim().to_lowercase();
+                if query == self.query {
+                    return false;
+                }
+                self.query = query;
+                self.hits.clear();
+                if self.query.is_empty() {
+                    self.restore_folds();
+                    return true;
+                }
+                self.save_folds();
+                self.expand_matches();
+
+                let q = self.query.clone();
+                let path = format!("/menu/search?q={}", js_sys::encode_uri_component(&q));
+                ctx.link().send_future(async move {
+                    Msg::MenuSearchLoaded(q, api::get::<Vec<MenuHit>>(&path).await)
+                });
+                true
+            }
+            Msg::MenuSearchLoaded(query, hits) => {
+                // 忽略已经过时的检索结果
+                if query != self.query {
+                    return false;
+                }
+                match hits {
+                    Ok(hits) => {
+                        self.hits = hits.into_iter().map(|hit| hit.menu_id).collect();
+                        self.expand_matches();
+                        true
+                    }
+                    Err(e) => {
+                        log::warn!("{e}");
+                        false
+                    }
+                }
+            }
+            Msg::MenuSearchEnter => match self.first_match() {
+                Some(id) if id != self.activated => self.update(ctx, Msg::MenuClicked(id)),
+                _ => false,
+            },
             _ => false,
         }
     }
@@ -175,20 +305,33 @@ impl Component for Menu {
             None => return html! {},
         };
 
+        let oninput = ctx.link().callback(|e: InputEvent| {
+            Msg::MenuSearch(e.target_unchecked_into::<HtmlInputElement>().value())
+        });
+        let onkeydown = ctx.link().batch_callback(|e: KeyboardEvent| {
+            (e.key() == "Enter").then_some(Msg::MenuSearchEnter)
+        });
+
         html! {
             <aside class="menu">
-                {for root.children(&self.nodes).map(|child| self.nodes.view(child, ctx.link()))}
+                <div class="field">
+                    <p class="control">
+                        <input class="input is-small" type="search" placeholder={t("menu.search")}
+                            {oninput} {onkeydown} />
+                    </p>
+                </div>
+                {for root.children(&self.nodes).map(|child| self.nodes.view(child, ctx.link(), &self.query))}
             </aside>
         }
     }
 }
 
 trait MenuView {
-    fn view(&self, node_id: NodeId, link: &Scope<Menu>) -> Html;
+    fn view(&self, node_id: NodeId, link: &Scope<Menu>, query: &str) -> Html;
 }
 
 impl MenuView for Arena<MenuNode> {
-    fn view(&self, node_id: NodeId, link: &Scope<Menu>) -> Html {
+    fn view(&self, node_id: NodeId, link: &Scope<Menu>, query: &str) -> Html {
         let node = match self.get(node_id) {
             Some(node) => node.get(),
             None => return html! {},
@@ -201,10 +344,10 @@ impl MenuView for Arena<MenuNode> {
         match node.menu_type {
             MenuType::Label => html! {
                 <>
-                <div class="menu-label" onclick={onclick}> {&*node.text} </div>
+                <div class="menu-label" onclick={onclick}> {highlight(&node.text, query)} </div>
                 if node.expanded {
                      <ul class="menu-list">
-                        { for node_id.children(self).map(|child| self.view(child, link)) }
+                        { for node_id.children(self).map(|child| self.view(child, link, query)) }
                     </ul>
                 }
                 </>
@@ -212,11 +355,11 @@ impl MenuView for Arena<MenuNode> {
             MenuType::Fold => html! {
                 <li>
                     <a onclick={onclick}>
-                        {&*node.text}
+                        {highlight(&node.text, query)}
                     </a>
                     <ul>
                     if node.expanded {
-                        { for node_id.children(self).map(|child| self.view(child, link)) }
+                        { for node_id.children(self).map(|child| self.view(child, link, query)) }
                     }
                     </ul>
                 </li>
@@ -224,10 +367,52 @@ impl MenuView for Arena<MenuNode> {
             MenuType::Item => html! {
                 <li>
                     <a class={classes!(is_active)} onclick={onclick}>
-                        {&*node.text}
+                        {highlight(&node.text, query)}
                     </a>
                 </li>
             },
         }
     }
 }
+
+/// 用 mark 标出菜单文本中与搜索词匹配的部分
+fn highlight(text: &str, query: &str) -> Html {
+    let lower = text.to_lowercase();
+    // 小写后字节长度变化时无法对应回原文，直接整体显示
+    let start = match lower.find(query) {
+        Some(start) if !query.is_empty() && lower.len() == text.len() => start,
+        _ => return html! { text },
+    };
+    let end = start + query.len();
+
+    html! {
+        <>
+            {&text[..start]}
+            <mark>{&text[start..end]}</mark>
+            {&text[end..]}
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_marks_first_match() {
+        let expected = html! {
+            <>
+                {"Or"}
+                <mark>{"der"}</mark>
+                {"s"}
+            </>
+        };
+        assert_eq!(highlight("Orders", "der"), expected);
+    }
+
+    #[test]
+    fn highlight_keeps_text_without_match() {
+        assert_eq!(highlight("Orders", ""), html! { "Orders" });
+        assert_eq!(highlight("Orders", "xyz"), html! { "Orders" });
+    }
+}
@@ -4,10 +4,17 @@ use yew_router::Routable;
 
 use crate::app::components::sys1::{Fn1001, Fn1002};
 
+pub mod chart;
+pub mod dashboard;
+pub mod form;
+pub mod grid;
+pub mod import;
+pub mod layout;
 pub mod login;
 pub mod menu;
 pub mod sys1;
 pub mod sys2;
+pub mod view;
 
 #[derive(Clone, Routable, PartialEq, Eq, EnumString)]
 pub enum FnRoute {
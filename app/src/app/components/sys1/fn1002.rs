@@ -1,4 +1,5 @@
 use crate::app::context::{ContextExt, Module};
+use crate::app::i18n::t;
 use yew::prelude::*;
 
 use crate::app::msg::Msg;
@@ -19,10 +20,10 @@ impl Component for Fn1002 {
             <div class="box">
                 <div class="tabs is-toggle">
                     <ul>
-                        <li class="is-active"><a>{"Pictures"}</a></li>
-                        <li><a>{"Music"}</a></li>
-                        <li><a>{"Videos"}</a></li>
-                        <li><a>{"Documents"}</a></li>
+                        <li class="is-active"><a>{t("tab.pictures")}</a></li>
+                        <li><a>{t("tab.music")}</a></li>
+                        <li><a>{t("tab.videos")}</a></li>
+                        <li><a>{t("tab.documents")}</a></li>
                     </ul>
                 </div>
             </div>
@@ -1,5 +1,7 @@
 use yew::prelude::*;
 
+use crate::app::i18n::t;
+
 pub struct Sys2 {
     pub label: String,
 }
@@ -10,7 +12,7 @@ impl Component for Sys2 {
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
-            label: "Settings".to_owned(),
+            label: t("sys2.settings").to_owned(),
         }
     }
 
@@ -22,60 +24,60 @@ impl Component for Sys2 {
                         <aside class="menu">
                             <p class="menu-label">{&*self.label}</p>
                             <ul class="menu-list">
-                                <li><a>{"Dashboard"}</a></li>
-                                <li><a>{"Customers"}</a></li>
+                                <li><a>{t("sys2.dashboard")}</a></li>
+                                <li><a>{t("sys2.customers")}</a></li>
                             </ul>
-                            <p class="menu-label">{"Administration"}</p>
+                            <p class="menu-label">{t("sys2.administration")}</p>
                             <ul class="menu-list">
-                                <li><a>{"Team Settings"}</a></li>
+                                <li><a>{t("sys2.team_settings")}</a></li>
                                 <li>
-                                    <a class="is-active">{"Manage Your Team"}</a>
+                                    <a class="is-active">{t("sys2.manage_team")}</a>
                                     <ul>
-                                        <li><a>{"Members"}</a></li>
-                                        <li><a>{"Plugins"}</a></li>
-                                        <li><a>{"Add a member"}</a></li>
+                                        <li><a>{t("sys2.members")}</a></li>
+                                        <li><a>{t("sys2.plugins")}</a></li>
+                                        <li><a>{t("sys2.add_member")}</a></li>
                                     </ul>
                                 </li>
-                                <li><a>{"Invitations"}</a></li>
+                                <li><a>{t("sys2.invitations")}</a></li>
                             </ul>
-                            <p class="menu-label">{"Transactions"}</p>
+                            <p class="menu-label">{t("sys2.transactions")}</p>
                             <ul class="menu-list">
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
-                                <li><a>{"Payments"}</a></li>
-                                <li><a>{"Transfers"}</a></li>
-                                <li><a>{"Balance"}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
+                                <li><a>{t("sys2.payments")}</a></li>
+                                <li><a>{t("sys2.transfers")}</a></li>
+                                <li><a>{t("sys2.balance")}</a></li>
                             </ul>
                         </aside>
                     </div>
@@ -84,21 +86,21 @@ impl Component for Sys2 {
                     <div class="box">
                         <div class="tabs is-toggle">
                             <ul>
-                                <li class="is-active"><a>{"Pictures"}</a></li>
-                                <li><a>{"Music"}</a></li>
-                                <li><a>{"Videos"}</a></li>
-                                <li><a>{"Documents"}</a></li>
+                                <li class="is-active"><a>{t("tab.pictures")}</a></li>
+                                <li><a>{t("tab.music")}</a></li>
+                                <li><a>{t("tab.videos")}</a></li>
+                                <li><a>{t("tab.documents")}</a></li>
                             </ul>
                         </div>
                         <div class="field is-grouped is-grouped-multiline">
                             <p class="control has-icons-left">
-                                <input class="input" type="text" placeholder="Name" />
+                                <input class="input" type="text" placeholder={t("common.name")} />
                                 <span class="icon is-small is-left">
                                     <i class="fas fa-user"></i>
                                 </span>
                             </p>
                             <p class="control has-icons-left has-icons-right">
-                                <input class="input is-success" type="email" placeholder="Email" value="zoe211@163.com" />
+                                <input class="input is-success" type="email" placeholder={t("common.email")} value="zoe211@163.com" />
                                 <span class="icon is-small is-left">
                                     <i class="fas fa-envelope"></i>
                                 </span>
@@ -107,7 +109,7 @@ impl Component for Sys2 {
                                 </span>
                             </p>
                             <p class="control">
-                                <a class="button is-primary">{"Search"}</a>
+                                <a class="button is-primary">{t("common.search")}</a>
                             </p>
                         </div>
                         <div class="table-container">
@@ -158,20 +160,20 @@ impl Component for Sys2 {
                                 <div class="level-item">
                                     <div class="field has-addons">
                                         <div class="control">
-                                            <input class="input is-small" width="1rem" type="text" placeholder="Page No." />
+                                            <input class="input is-small" width="1rem" type="text" placeholder={t("page.no")} />
                                         </div>
                                         <div class="control">
-                                            <a class="button is-info is-small">{"Go"}</a>
+                                            <a class="button is-info is-small">{t("page.go")}</a>
                                         </div>
                                     </div>
                                 </div>
                                 <div class="level-item">
                                     <div class="field has-addons">
                                         <div class="control">
-                                            <input class="input is-small" type="text" placeholder="Rows/Page" />
+                                            <input class="input is-small" type="text" placeholder={t("page.rows")} />
                                         </div>
                                         <div class="control">
-                                            <a class="button is-info is-small">{"Set"}</a>
+                                            <a class="button is-info is-small">{t("page.set")}</a>
                                         </div>
                                     </div>
                                 </div>
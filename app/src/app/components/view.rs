@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlSelectElement;
+use yew::{
+    classes, html, Callback, Component, Context, Event, Html, MouseEvent, Properties, TargetCast,
+};
+
+use crate::app::api;
+use crate::app::i18n::t;
+use crate::app::msg::Msg;
+
+/// 对应后端的 SortKey
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct SortKey {
+    pub column: String,
+    pub desc: Option<bool>,
+}
+
+/// 视图保存的表格状态
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct ViewSettings {
+    pub conditions: HashMap<String, String>,
+    pub sort: Vec<SortKey>,
+    /// 显示的列及顺序，为空时显示全部
+    pub columns: Vec<String>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct View {
+    pub view_id: Option<u32>,
+    /// 共享给该角色，为空时只属于当前用户
+    pub role: Option<String>,
+    pub table_name: String,
+    pub view_name: String,
+    pub settings: ViewSettings,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Debug, Default)]
+pub struct ViewList {
+    /// 网关认证的当前用户，匿名时为空
+    #[serde(default)]
+    pub user_id: Option<String>,
+    pub roles: Vec<String>,
+    pub views: Vec<View>,
+    pub default_view: Option<u32>,
+}
+
+impl ViewList {
+    /// 视图保存到本人或共享给角色，两者都没有时只能选择视图
+    pub fn editable(&self) -> bool {
+        self.user_id.is_some() || !self.roles.is_empty()
+    }
+}
+
+/// 对应后端的 DefaultView
+#[derive(Serialize)]
+struct DefaultView<'a> {
+    page_id: i32,
+    view_id: Option<u32>,
+    role: Option<&'a str>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ViewBarProps {
+    pub table_name: String,
+    /// 菜单项的页面编号，设置后可以选择默认视图
+    #[prop_or_default]
+    pub page_id: Option<i32>,
+    /// 表格当前的状态，保存视图时使用
+    pub settings: ViewSettings,
+    pub onapply: Callback<ViewSettings>,
+}
+
+/// 视图下拉框：选择、保存、共享和删除视图，打开时应用菜单项的默认视图
+pub struct ViewBar {
+    list: ViewList,
+    selected: Option<u32>,
+    /// 保存或设为默认时的目标角色，为空时为当前用户
+    share: Option<String>,
+    /// 首次加载后应用默认视图
+    loaded: bool,
+    busy: bool,
+    error: Option<String>,
+}
+
+impl Component for ViewBar {
+    type Message = Msg;
+    type Properties = ViewBarProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        load(ctx);
+
+        Self {
+            list: ViewList::default(),
+            selected: None,
+            share: None,
+            loaded: false,
+            busy: false,
+            error: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let props = ctx.props();
+        match msg {
+            Msg::ViewsLoaded(list) => {
+                match list {
+                    Ok(list) => {
+                        let default_view = list.default_view.filter(|_| !self.loaded);
+                        // 没有用户标识时只能保存到角色
+                        if list.user_id.is_none() && self.share.is_none() {
+                            self.share = list.roles.first().cloned();
+                        }
+                        self.list = list;
+                        self.loaded = true;
+                        self.error = None;
+                        if default_view.is_some() {
+                            ctx.link().send_message(Msg::ViewSelected(default_view));
+                        }
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            Msg::ViewSelected(view_id) => {
+                self.selected = view_id;
+                let settings = self
+                    .find(view_id)
+                    .map(|v| v.settings.clone())
+                    .unwrap_or_default();
+                self.share = self.find(view_id).and_then(|v| v.role.clone());
+                props.onapply.emit(settings);
+                true
+            }
+            Msg::ViewShare(role) => {
+                self.share = role;
+                true
+            }
+            Msg::ViewSave(as_new) => {
+                let current = self.find(self.selected).filter(|_| !as_new);
+                let view_name = match current {
+                    Some(view) => view.view_name.clone(),
+                    None => match prompt(t("view.name_prompt")) {
+                        Some(name) => name,
+                        None => return false,
+                    },
+                };
+                let view = View {
+                    view_id: current.and_then(|v| v.view_id),
+                    role: self.share.clone(),
+                    table_name: props.table_name.clone(),
+                    view_name,
+                    settings: props.settings.clone(),
+                };
+                self.busy = true;
+                ctx.link().send_future(async move {
+                    let res = api::post::<_, u32>("/view", &view).await;
+                    Msg::ViewSaved(res.map(Some))
+                });
+                true
+            }
+            Msg::ViewDelete => {
+                let view_id = match self.selected {
+                    Some(view_id) => view_id,
+                    None => return false,
+                };
+                if !confirm(t("view.delete_confirm")) {
+                    return false;
+                }
+                self.busy = true;
+                ctx.link().send_future(async move {
+                    let res = api::delete::<_, u64>(&format!("/view/id/{view_id}"), &()).await;
+                    Msg::ViewSaved(res.map(|_| None))
+                });
+                true
+            }
+            Msg::ViewDefault => {
+                let page_id = match props.page_id {
+                    Some(page_id) => page_id,
+                    None => return false,
+                };
+                let view_id = self.selected;
+                let role = self.share.clone();
+                self.busy = true;
+                ctx.link().send_future(async move {
+                    let req = DefaultView {
+                        page_id,
+                        view_id,
+                        role: role.as_deref(),
+                    };
+                    let res = api::put::<_, u64>("/view/default", &req).await;
+                    Msg::ViewSaved(res.map(|_| view_id))
+                });
+                true
+            }
+            Msg::ViewSaved(res) => {
+                self.busy = false;
+                match res {
+                    Ok(view_id) => {
+                        self.selected = view_id;
+                        load(ctx);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onselect = ctx.link().callback(|e: Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            Msg::ViewSelected(value.parse().ok())
+        });
+        let onshare = ctx.link().callback(|e: Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            Msg::ViewShare((!value.is_empty()).then_some(value))
+        });
+        let save = ctx.link().callback(|_: MouseEvent| Msg::ViewSave(false));
+        let save_as = ctx.link().callback(|_: MouseEvent| Msg::ViewSave(true));
+        let delete = ctx.link().callback(|_: MouseEvent| Msg::ViewDelete);
+        let set_default = ctx.link().callback(|_: MouseEvent| Msg::ViewDefault);
+        let is_loading = self.busy.then_some("is-loading");
+        let share = self.share.clone().unwrap_or_default();
+        let editable = self.list.editable();
+
+        html! {
+            <div class="block">
+                <div class="field is-grouped is-grouped-multiline">
+                    <div class="control">
+                        <div class="select is-small">
+                            <select onchange={onselect}>
+                                <option value="" selected={self.selected.is_none()}>{t("view.none")}</option>
+                                {for self.list.views.iter().map(|v| {
+                                    let view_id = v.view_id.unwrap_or_default();
+                                    let default = (self.list.default_view == v.view_id).then_some(t("view.default_mark"));
+                                    html! {
+                                        <option value={view_id.to_string()} selected={self.selected == v.view_id}>
+                                            {&*v.view_name}
+                                            {v.role.as_ref().map(|r| format!(" ({r})")).unwrap_or_default()}
+                                            {default.unwrap_or_default()}
+                                        </option>
+                                    }
+                                })}
+                            </select>
+                        </div>
+                    </div>
+                    if editable {
+                        <div class="control">
+                            <div class="select is-small">
+                                <select onchange={onshare} title={t("view.share")}>
+                                    if self.list.user_id.is_some() {
+                                        <option value="" selected={share.is_empty()}>{t("view.share_self")}</option>
+                                    }
+                                    {for self.list.roles.iter().map(|r| html! {
+                                        <option value={r.clone()} selected={*r == share}>{r}</option>
+                                    })}
+                                </select>
+                            </div>
+                        </div>
+                        <div class="control">
+                            <div class="buttons has-addons">
+                                <button class={classes!("button", "is-small", is_loading)}
+                                    disabled={self.busy || self.selected.is_none()} onclick={save}>{t("view.save")}</button>
+                                <button class={classes!("button", "is-small", is_loading)}
+                                    disabled={self.busy} onclick={save_as}>{t("view.save_as")}</button>
+                                if props.page_id.is_some() {
+                                    <button class={classes!("button", "is-small", is_loading)}
+                                        disabled={self.busy} onclick={set_default}>{t("view.set_default")}</button>
+                                }
+                                <button class={classes!("button", "is-small", "is-danger", "is-light", is_loading)}
+                                    disabled={self.busy || self.selected.is_none()} onclick={delete}>{t("view.delete")}</button>
+                            </div>
+                        </div>
+                    }
+                </div>
+                if let Some(e) = &self.error {
+                    <div class="notification is-danger is-light">{e}</div>
+                }
+            </div>
+        }
+    }
+}
+
+impl ViewBar {
+    fn find(&self, view_id: Option<u32>) -> Option<&View> {
+        view_id.and_then(|id| self.list.views.iter().find(|v| v.view_id == Some(id)))
+    }
+}
+
+fn load(ctx: &Context<ViewBar>) {
+    let props = ctx.props();
+    let mut path = format!("/view/{}", props.table_name);
+    if let Some(page_id) = props.page_id {
+        path += &format!("?page_id={page_id}");
+    }
+    ctx.link()
+        .send_future(async move { Msg::ViewsLoaded(api::get(&path).await) });
+}
+
+fn prompt(message: &str) -> Option<String> {
+    web_sys::window()
+        .and_then(|w| w.prompt_with_message(message).ok())
+        .flatten()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.confirm_with_message(message).ok())
+        .unwrap_or_default()
+}
@@ -7,6 +7,8 @@ use yew::scheduler::Shared;
 use yew::Callback;
 use yew::{Component, Context};
 
+use crate::app::i18n::t;
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Module {
     Sys1,
@@ -64,6 +66,9 @@ impl<COMP: Component> ContextExt for Context<COMP> {
                 }
             }
         }
-        log::error!("往模块{:?}发送消息失败", module);
+        log::error!(
+            "{}",
+            t("context.send_failed").replace("{}", &format!("{:?}", module))
+        );
     }
 }
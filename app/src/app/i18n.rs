@@ -0,0 +1,114 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 界面语言，切换后保存到 localStorage 并刷新页面
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Lang {
+    #[default]
+    ZhCn,
+    En,
+}
+
+const STORAGE_KEY: &str = "lang";
+
+type Bundle = HashMap<String, String>;
+
+static BUNDLES: OnceLock<(Bundle, Bundle)> = OnceLock::new();
+
+thread_local! {
+    static LANG: Cell<Lang> = Cell::new(detect());
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::ZhCn, Lang::En];
+
+    /// 与后端 `Accept-Language` 及 table_columns_i18n.LOCALE 一致
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::ZhCn => "zh-CN",
+            Lang::En => "en",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lang::ZhCn => "中文",
+            Lang::En => "English",
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code.split('-').next()?.to_ascii_lowercase().as_str() {
+            "zh" => Some(Lang::ZhCn),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    fn bundle(self) -> &'static Bundle {
+        let (zh_cn, en) = BUNDLES.get_or_init(|| {
+            let parse = |json: &str| serde_json::from_str::<Bundle>(json).unwrap_or_default();
+            (
+                parse(include_str!("../../i18n/zh-CN.json")),
+                parse(include_str!("../../i18n/en.json")),
+            )
+        });
+        match self {
+            Lang::ZhCn => zh_cn,
+            Lang::En => en,
+        }
+    }
+}
+
+/// 先取用户选择的语言，其次取浏览器语言
+fn detect() -> Lang {
+    let window = match web_sys::window() {
+        Some(w) => w,
+        None => return Lang::default(),
+    };
+    window
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|s| s.get_item(STORAGE_KEY).ok().flatten())
+        .and_then(|code| Lang::from_code(&code))
+        .or_else(|| {
+            window
+                .navigator()
+                .language()
+                .and_then(|code| Lang::from_code(&code))
+        })
+        .unwrap_or_default()
+}
+
+pub fn lang() -> Lang {
+    LANG.with(Cell::get)
+}
+
+/// 切换语言，刷新页面使全部组件和后端数据使用新的语言
+pub fn set_lang(lang: Lang) {
+    if lang == self::lang() {
+        return;
+    }
+    LANG.with(|l| l.set(lang));
+    if let Some(window) = web_sys::window() {
+        if let Ok(Some(storage)) = window.local_storage() {
+            storage.set_item(STORAGE_KEY, lang.code()).ok();
+        }
+        window.location().reload().ok();
+    }
+}
+
+/// 翻译，找不到时回退到中文，再找不到时原样返回
+pub fn t(key: &'static str) -> &'static str {
+    lang()
+        .bundle()
+        .get(key)
+        .or_else(|| Lang::ZhCn.bundle().get(key))
+        .map(String::as_str)
+        .unwrap_or_else(|| {
+            log::warn!("缺少翻译：{}", key);
+            key
+        })
+}
@@ -8,10 +8,13 @@ use yew_router::{components::Link, BrowserRouter, Switch};
 use msg::Msg;
 
 use crate::app::context::AppContext;
+use crate::app::i18n::{t, Lang};
 use crate::app::route::{switch, AppRoute};
 
+pub mod api;
 pub mod components;
 pub mod context;
+pub mod i18n;
 pub mod msg;
 mod route;
 
@@ -37,6 +40,10 @@ impl Component for App {
                 self.bugger_switch = !self.bugger_switch;
                 true
             }
+            Msg::LangSwitch(lang) => {
+                i18n::set_lang(lang);
+                false
+            }
             _ => false,
         }
     }
@@ -65,44 +72,56 @@ impl Component for App {
               <div id="navbarBasicExample" class={classes!("navbar-menu", navbar_class)}>
                 <div class="navbar-start">
                     <Link<AppRoute> classes={"navbar-item"} to={AppRoute::Sys1 { fn_name: "fn1001".to_string() }}>
-                        { "Sys1" }
+                        { t("nav.sys1") }
                     </Link<AppRoute>>
                     <Link<AppRoute> classes={"navbar-item"} to={AppRoute::Sys2 { fn_name: "fn2001".to_string()}}>
-                        {"Sys2"}
+                        {t("nav.sys2")}
                     </Link<AppRoute>>
 
                   <div class="navbar-item has-dropdown is-hoverable">
                     <a class="navbar-link">
-                      {"More"}
+                      {t("nav.more")}
                     </a>
 
                     <div class="navbar-dropdown">
                       <a class="navbar-item">
-                      {"About"}
+                      {t("nav.about")}
                       </a>
                       <a class="navbar-item">
-                      {"Jobs"}
+                      {t("nav.jobs")}
                       </a>
                       <a class="navbar-item">
-                      {"Contact"}
+                      {t("nav.contact")}
                       </a>
                       <hr class="navbar-divider" />
                       <a class="navbar-item">
-                      {"Report an issue"}
+                      {t("nav.report_issue")}
                       </a>
                     </div>
                   </div>
                 </div>
 
                 <div class="navbar-end">
+                  <div class="navbar-item has-dropdown is-hoverable">
+                    <a class="navbar-link">{i18n::lang().name()}</a>
+                    <div class="navbar-dropdown is-right">
+                      {for Lang::ALL.iter().map(|&lang| {
+                          let onclick = ctx.link().callback(move |_| Msg::LangSwitch(lang));
+                          let active = (lang == i18n::lang()).then_some("is-active");
+                          html! {
+                              <a class={classes!("navbar-item", active)} {onclick}>{lang.name()}</a>
+                          }
+                      })}
+                    </div>
+                  </div>
                   <div class="navbar-item">
                     <div class="buttons">
                       <a class="button is-primary">
-                        <strong>{"Sign up"}</strong>
-                      </a>
-                      <a class="button is-light">
-                      {"Log in"}
+                        <strong>{t("nav.sign_up")}</strong>
                       </a>
+                      <Link<AppRoute> classes={"button is-light"} to={AppRoute::Login}>
+                      {t("nav.log_in")}
+                      </Link<AppRoute>>
                     </div>
                   </div>
                 </div>
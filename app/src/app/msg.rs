@@ -1,5 +1,90 @@
+use std::collections::HashMap;
+
+use crate::app::components::chart::AggregateResult;
+use crate::app::components::dashboard::{DashboardInfo, Widget, WidgetField};
+use crate::app::components::form::{ColumnInfo, DictKey};
+use crate::app::components::grid::{Relation, Row};
+use crate::app::components::import::{ImportProgress, ImportReport};
+use crate::app::components::layout::GridLayout;
+use crate::app::components::menu::MenuHit;
+use crate::app::components::view::{ViewList, ViewSettings};
+use crate::app::i18n::Lang;
+
 #[derive(Debug)]
 pub enum Msg {
     BuggerClick,
+    LangSwitch(Lang),
     MenuClicked(u32),
+    MenuSearch(String),
+    /// (搜索词, 后端检索结果)
+    MenuSearchLoaded(String, Result<Vec<MenuHit>, String>),
+    MenuSearchEnter,
+    DictLoaded(Vec<DictKey>),
+    FieldInput(String),
+    FieldToggle(String),
+    ColumnsLoaded(Vec<ColumnInfo>),
+    FormChanged(String, String),
+    FormSubmit,
+    Search(HashMap<String, String>),
+    RowsLoaded(Result<Vec<Row>, String>),
+    /// 符合查询条件的总行数
+    CountLoaded(Result<u64, String>),
+    RelationsLoaded(Vec<Relation>),
+    /// 从表删除单行所需的 d 列
+    DeleteColumnsLoaded(Vec<ColumnInfo>),
+    RowSelected(usize),
+    Create(HashMap<String, String>),
+    /// 删除 rows 中的第几行
+    Delete(usize),
+    /// 新增或删除影响的行数
+    Saved(Result<u64, String>),
+    Export(&'static str),
+    Exported(Result<(), String>),
+    ImportFile(Option<web_sys::File>),
+    ImportUpsert,
+    /// 是否只校验
+    ImportSubmit(bool),
+    ImportReported(Result<ImportReport, String>),
+    ImportProgressed(Result<ImportProgress, String>),
+    WidgetsLoaded(Result<DashboardInfo, String>),
+    /// 打开编辑器，None 时关闭
+    WidgetEdit(Option<Widget>),
+    /// 编辑器中的组件已保存或删除
+    WidgetSaved,
+    WidgetInput(WidgetField, String),
+    WidgetSave,
+    WidgetDelete,
+    AggregateLoaded(Result<AggregateResult, String>),
+    ViewsLoaded(Result<ViewList, String>),
+    /// None 时不使用视图
+    ViewSelected(Option<u32>),
+    /// 保存或设为默认的目标角色，None 时为当前用户
+    ViewShare(Option<String>),
+    /// 是否另存为新视图
+    ViewSave(bool),
+    ViewDelete,
+    ViewDefault,
+    /// 保存后选中的视图
+    ViewSaved(Result<Option<u32>, String>),
+    ViewApplied(ViewSettings),
+    /// 按该列切换排序
+    Sort(String),
+    /// 从 0 开始的页码
+    Page(usize),
+    PageSize(usize),
+    LayoutLoaded(Result<GridLayout, String>),
+    LayoutSaved(Result<u64, String>),
+    /// 显示或隐藏该列
+    ColumnToggle(String),
+    ColumnFrozen(u32),
+    LayoutReset,
+    ChooserToggle,
+    /// 开始拖动该列的表头
+    ColumnDragStart(String),
+    /// 放到该列的位置，None 时拖动取消
+    ColumnDrop(Option<String>),
+    /// (列名, 指针横坐标, 列的当前宽度)
+    ResizeStart(String, i32, u32),
+    ResizeMove(i32),
+    ResizeEnd,
 }
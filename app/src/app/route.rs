@@ -1,7 +1,7 @@
 use yew::{html, Html};
 use yew_router::Routable;
 
-use crate::app::components::{login::Login, sys1::Sys1, sys2::Sys2};
+use crate::app::components::{dashboard::Dashboard, login::Login, sys1::Sys1, sys2::Sys2};
 
 #[derive(Clone, Routable, PartialEq, Eq)]
 pub enum AppRoute {
@@ -9,6 +9,8 @@ pub enum AppRoute {
     Sys1 { fn_name: String },
     #[at("/sys2/:fn_name")]
     Sys2 { fn_name: String },
+    #[at("/login")]
+    Login,
     #[at("/")]
     Welcome,
 }
@@ -21,8 +23,11 @@ pub fn switch(routes: AppRoute) -> Html {
         AppRoute::Sys2 { fn_name: _ } => html! {
             <Sys2 />
         },
-        AppRoute::Welcome => html! {
+        AppRoute::Login => html! {
             <Login />
         },
+        AppRoute::Welcome => html! {
+            <Dashboard />
+        },
     }
 }
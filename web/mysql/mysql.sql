@@ -57,6 +57,76 @@ CREATE TABLE `table_columns`
     PRIMARY KEY (TABLE_NAME, `COLUMN_NAME`)
 ) COMMENT = '更新字段';
 
+DROP TABLE IF EXISTS `table_columns_i18n`;
+CREATE TABLE `table_columns_i18n`
+(
+    `TABLE_NAME`  VARCHAR(255) NOT NULL COMMENT '表名',
+    `COLUMN_NAME` VARCHAR(255) NOT NULL COMMENT '列名',
+    `LOCALE`      VARCHAR(16)  NOT NULL COMMENT '语言 zh-CN, en',
+    `COLUMN_DESC` VARCHAR(255) NOT NULL COMMENT '列描述',
+    PRIMARY KEY (`TABLE_NAME`, `COLUMN_NAME`, `LOCALE`)
+) COMMENT = '列描述多语言';
+
+DROP TABLE IF EXISTS `table_relations`;
+CREATE TABLE `table_relations`
+(
+    `TABLE_NAME`    VARCHAR(255) NOT NULL COMMENT '从表名',
+    `COLUMN_NAME`   VARCHAR(255) NOT NULL COMMENT '从表外键列',
+    `PARENT_TABLE`  VARCHAR(255) NOT NULL COMMENT '主表名',
+    `PARENT_COLUMN` VARCHAR(255) NOT NULL COMMENT '主表关联列',
+    `LABEL_COLUMN`  VARCHAR(255) COMMENT '查询从表时作为外键标签的主表列',
+    PRIMARY KEY (`TABLE_NAME`, `COLUMN_NAME`),
+    INDEX `IDX_RELATION_PARENT` (`PARENT_TABLE`)
+) COMMENT = '主从表关联';
+
+DROP TABLE IF EXISTS `dashboard_widget`;
+CREATE TABLE `dashboard_widget`
+(
+    `WIDGET_ID`    INT UNSIGNED NOT NULL AUTO_INCREMENT COMMENT '组件编号',
+    `OWNER_TYPE`   CHAR         NOT NULL COMMENT '所属类型 u-用户 r-角色',
+    `OWNER_ID`     VARCHAR(255) NOT NULL COMMENT '用户或角色标识',
+    `WIDGET_TYPE`  VARCHAR(16)  NOT NULL COMMENT '组件类型 kpi, bar, line, pie',
+    `TITLE`        VARCHAR(255) NOT NULL COMMENT '标题',
+    `TABLE_NAME`   VARCHAR(255) NOT NULL COMMENT '表名',
+    `QUERY`        TEXT         NOT NULL COMMENT '聚合条件 JSON: group_by, aggregates, conditions',
+    `WIDGET_ORDER` INT          NOT NULL DEFAULT 0 COMMENT '显示顺序',
+    PRIMARY KEY (`WIDGET_ID`),
+    INDEX `IDX_WIDGET_OWNER` (`OWNER_TYPE`, `OWNER_ID`, `WIDGET_ORDER`)
+) COMMENT = '仪表盘组件';
+
+DROP TABLE IF EXISTS `saved_view`;
+CREATE TABLE `saved_view`
+(
+    `VIEW_ID`    INT UNSIGNED NOT NULL AUTO_INCREMENT COMMENT '视图编号',
+    `OWNER_TYPE` CHAR         NOT NULL COMMENT '所属类型 u-用户 r-角色',
+    `OWNER_ID`   VARCHAR(255) NOT NULL COMMENT '用户或角色标识',
+    `TABLE_NAME` VARCHAR(255) NOT NULL COMMENT '表名',
+    `VIEW_NAME`  VARCHAR(255) NOT NULL COMMENT '视图名称',
+    `SETTINGS`   TEXT         NOT NULL COMMENT '视图设置 JSON: conditions, sort, columns, page_size',
+    PRIMARY KEY (`VIEW_ID`),
+    UNIQUE INDEX `UK_VIEW_NAME` (`OWNER_TYPE`, `OWNER_ID`, `TABLE_NAME`, `VIEW_NAME`)
+) COMMENT = '表格视图';
+
+DROP TABLE IF EXISTS `default_view`;
+CREATE TABLE `default_view`
+(
+    `OWNER_TYPE` CHAR         NOT NULL COMMENT '所属类型 u-用户 r-角色',
+    `OWNER_ID`   VARCHAR(255) NOT NULL COMMENT '用户或角色标识',
+    `PAGE_ID`    INT          NOT NULL COMMENT '菜单项的页面编号 menu_info.PAGE_ID',
+    `VIEW_ID`    INT UNSIGNED NOT NULL COMMENT '视图编号',
+    PRIMARY KEY (`OWNER_TYPE`, `OWNER_ID`, `PAGE_ID`),
+    INDEX `IDX_DEFAULT_VIEW` (`VIEW_ID`)
+) COMMENT = '菜单项的默认视图';
+
+DROP TABLE IF EXISTS `grid_layout`;
+CREATE TABLE `grid_layout`
+(
+    `OWNER_ID`   VARCHAR(255) NOT NULL COMMENT '用户标识',
+    `TABLE_NAME` VARCHAR(255) NOT NULL COMMENT '表名',
+    `LAYOUT`     TEXT         NOT NULL COMMENT '表格布局 JSON: columns, hidden, widths, frozen',
+    PRIMARY KEY (`OWNER_ID`, `TABLE_NAME`)
+) COMMENT = '用户的表格布局';
+
 DROP TABLE IF EXISTS `dict_info`;
 CREATE TABLE `dict_info`
 (
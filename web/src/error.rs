@@ -1,5 +1,25 @@
+use poem::error::ResponseError;
+use poem::http::header::RETRY_AFTER;
+use poem::http::StatusCode;
+use poem::{IntoResponse, Response};
+use poem_openapi::registry::{MetaMediaType, MetaResponse, MetaResponses, Registry};
+use poem_openapi::types::Type;
+use poem_openapi::{ApiResponse, Object};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::mysql::MySqlDatabaseError;
 use thiserror::Error;
 
+use crate::web::i18n;
+use crate::web::request_id;
+
+// MySQL 错误码
+const ER_DUP_ENTRY: u16 = 1062;
+const ER_ROW_IS_REFERENCED: u16 = 1451;
+const ER_NO_REFERENCED_ROW: u16 = 1452;
+const ER_LOCK_WAIT_TIMEOUT: u16 = 1205;
+const ER_LOCK_DEADLOCK: u16 = 1213;
+
 #[derive(Error, Debug)]
 pub(crate) enum TransError {
     // #[error("data store disconnected")]
@@ -14,4 +34,355 @@ pub(crate) enum TransError {
     RequestMustContain(String),
     #[error("crud_info for table_name[`{0}`] and crud_type[`{1}`] is not found")]
     CrudInfoNotFound(String, String),
+    #[error("CSRF token is missing or does not match")]
+    CsrfTokenMismatch,
+    #[error("Request contains more than {1} {0}")]
+    TooManyFields(String, usize),
+    #[error("Menu {0} not found")]
+    MenuNotFound(u32),
+    #[error("Dict {0} not found")]
+    DictNotFound(i32),
+    #[error("Too many requests, retry after {0} seconds")]
+    TooManyRequests(u64),
+    #[error("Export failed: {0}")]
+    Export(String),
+    #[error("Import file is invalid: {0}")]
+    InvalidImportFile(String),
+    #[error("Import job {0} not found")]
+    ImportJobNotFound(String),
+    #[error("Relation between table `{0}` and parent table `{1}` is not found")]
+    RelationNotFound(String, String),
+    #[error("Column `{0}` cannot be used in this aggregate")]
+    InvalidAggregate(String),
+    #[error("Column `{0}` cannot be used for sorting")]
+    InvalidSort(String),
+    #[error("Request must carry a user identity")]
+    UserRequired,
+    #[error("Role `{0}` is not granted to the current user")]
+    RoleNotGranted(String),
+    #[error("Widget {0} not found")]
+    WidgetNotFound(u32),
+    #[error("View {0} not found")]
+    ViewNotFound(u32),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+    /// poem 及 poem-openapi 产生的其它错误
+    #[error("{1}")]
+    Http(StatusCode, String),
+}
+
+impl TransError {
+    /// 错误类型名称，用于指标标签
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            TransError::RequestMustContain(_) => "RequestMustContain",
+            TransError::CrudInfoNotFound(..) => "CrudInfoNotFound",
+            TransError::CsrfTokenMismatch => "CsrfTokenMismatch",
+            TransError::TooManyFields(..) => "TooManyFields",
+            TransError::MenuNotFound(_) => "MenuNotFound",
+            TransError::DictNotFound(_) => "DictNotFound",
+            TransError::TooManyRequests(_) => "TooManyRequests",
+            TransError::Export(_) => "Export",
+            TransError::InvalidImportFile(_) => "InvalidImportFile",
+            TransError::ImportJobNotFound(_) => "ImportJobNotFound",
+            TransError::RelationNotFound(..) => "RelationNotFound",
+            TransError::InvalidAggregate(_) => "InvalidAggregate",
+            TransError::InvalidSort(_) => "InvalidSort",
+            TransError::UserRequired => "UserRequired",
+            TransError::RoleNotGranted(_) => "RoleNotGranted",
+            TransError::WidgetNotFound(_) => "WidgetNotFound",
+            TransError::ViewNotFound(_) => "ViewNotFound",
+            TransError::Database(_) => "Database",
+            TransError::Http(..) => "Http",
+        }
+    }
+
+    /// 返回给前端的错误码，发布后不再修改
+    pub(crate) fn code(&self) -> String {
+        match self {
+            TransError::RequestMustContain(_) => "REQUEST_MUST_CONTAIN".to_string(),
+            TransError::CrudInfoNotFound(..) => "CRUD_INFO_NOT_FOUND".to_string(),
+            TransError::CsrfTokenMismatch => "CSRF_TOKEN_MISMATCH".to_string(),
+            TransError::TooManyFields(..) => "TOO_MANY_FIELDS".to_string(),
+            TransError::MenuNotFound(_) => "MENU_NOT_FOUND".to_string(),
+            TransError::DictNotFound(_) => "DICT_NOT_FOUND".to_string(),
+            TransError::TooManyRequests(_) => "TOO_MANY_REQUESTS".to_string(),
+            TransError::Export(_) => "EXPORT_FAILED".to_string(),
+            TransError::InvalidImportFile(_) => "INVALID_IMPORT_FILE".to_string(),
+            TransError::ImportJobNotFound(_) => "IMPORT_JOB_NOT_FOUND".to_string(),
+            TransError::RelationNotFound(..) => "RELATION_NOT_FOUND".to_string(),
+            TransError::InvalidAggregate(_) => "INVALID_AGGREGATE".to_string(),
+            TransError::InvalidSort(_) => "INVALID_SORT".to_string(),
+            TransError::UserRequired => "USER_REQUIRED".to_string(),
+            TransError::RoleNotGranted(_) => "ROLE_NOT_GRANTED".to_string(),
+            TransError::WidgetNotFound(_) => "WIDGET_NOT_FOUND".to_string(),
+            TransError::ViewNotFound(_) => "VIEW_NOT_FOUND".to_string(),
+            TransError::Database(e) => match mysql_code(e) {
+                Some(ER_DUP_ENTRY) => "DUPLICATE_KEY".to_string(),
+                Some(ER_ROW_IS_REFERENCED | ER_NO_REFERENCED_ROW) => {
+                    "FOREIGN_KEY_VIOLATION".to_string()
+                }
+                _ if retryable(e) => "DATABASE_BUSY".to_string(),
+                _ => "DATABASE_ERROR".to_string(),
+            },
+            TransError::Http(status, _) => status
+                .canonical_reason()
+                .unwrap_or("UNKNOWN")
+                .to_uppercase()
+                .replace([' ', '-'], "_"),
+        }
+    }
+
+    /// 返回给前端的错误信息，按请求语言翻译，数据库及服务端错误不暴露原始信息
+    fn message(&self) -> String {
+        match self {
+            TransError::RequestMustContain(field) => {
+                i18n::tr("error.request_must_contain", &[("field", field)])
+            }
+            TransError::CrudInfoNotFound(table_name, crud_type) => i18n::tr(
+                "error.crud_info_not_found",
+                &[("table_name", table_name), ("crud_type", crud_type)],
+            ),
+            TransError::CsrfTokenMismatch => i18n::tr("error.csrf_token_mismatch", &[]),
+            TransError::TooManyFields(field, max) => {
+                i18n::tr("error.too_many_fields", &[("field", field), ("max", max)])
+            }
+            TransError::MenuNotFound(menu_id) => {
+                i18n::tr("error.menu_not_found", &[("menu_id", menu_id)])
+            }
+            TransError::DictNotFound(dict_id) => {
+                i18n::tr("error.dict_not_found", &[("dict_id", dict_id)])
+            }
+            TransError::TooManyRequests(secs) => {
+                i18n::tr("error.too_many_requests", &[("secs", secs)])
+            }
+            TransError::Export(_) => i18n::tr("error.export", &[]),
+            TransError::InvalidImportFile(e) => {
+                i18n::tr("error.invalid_import_file", &[("reason", e)])
+            }
+            TransError::ImportJobNotFound(job_id) => {
+                i18n::tr("error.import_job_not_found", &[("job_id", job_id)])
+            }
+            TransError::RelationNotFound(table_name, parent_table) => i18n::tr(
+                "error.relation_not_found",
+                &[("table_name", table_name), ("parent_table", parent_table)],
+            ),
+            TransError::InvalidAggregate(column) => {
+                i18n::tr("error.invalid_aggregate", &[("column", column)])
+            }
+            TransError::InvalidSort(column) => {
+                i18n::tr("error.invalid_sort", &[("column", column)])
+            }
+            TransError::UserRequired => i18n::tr("error.user_required", &[]),
+            TransError::RoleNotGranted(role) => {
+                i18n::tr("error.role_not_granted", &[("role", role)])
+            }
+            TransError::WidgetNotFound(widget_id) => {
+                i18n::tr("error.widget_not_found", &[("widget_id", widget_id)])
+            }
+            TransError::ViewNotFound(view_id) => {
+                i18n::tr("error.view_not_found", &[("view_id", view_id)])
+            }
+            TransError::Database(e) => match mysql_code(e) {
+                Some(ER_DUP_ENTRY) => i18n::tr("error.duplicate_key", &[]),
+                Some(ER_ROW_IS_REFERENCED) => i18n::tr("error.row_is_referenced", &[]),
+                Some(ER_NO_REFERENCED_ROW) => i18n::tr("error.no_referenced_row", &[]),
+                _ if retryable(e) => i18n::tr("error.database_busy", &[]),
+                _ => i18n::tr("error.database", &[]),
+            },
+            TransError::Http(status, _) if status.is_server_error() => {
+                i18n::tr("error.internal", &[])
+            }
+            TransError::Http(status, msg) => match *status {
+                StatusCode::NOT_FOUND => i18n::tr("error.not_found", &[]),
+                StatusCode::METHOD_NOT_ALLOWED => i18n::tr("error.method_not_allowed", &[]),
+                StatusCode::PAYLOAD_TOO_LARGE => i18n::tr("error.payload_too_large", &[]),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE => i18n::tr("error.unsupported_media_type", &[]),
+                _ => msg.clone(),
+            },
+        }
+    }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            TransError::RequestMustContain(field) => Some(json!({ "field": field })),
+            TransError::CrudInfoNotFound(table_name, crud_type) => {
+                Some(json!({ "table_name": table_name, "crud_type": crud_type }))
+            }
+            TransError::TooManyFields(field, max) => Some(json!({ "field": field, "max": max })),
+            TransError::MenuNotFound(menu_id) => Some(json!({ "menu_id": menu_id })),
+            TransError::DictNotFound(dict_id) => Some(json!({ "dict_id": dict_id })),
+            TransError::ImportJobNotFound(job_id) => Some(json!({ "job_id": job_id })),
+            TransError::RelationNotFound(table_name, parent_table) => {
+                Some(json!({ "table_name": table_name, "parent_table": parent_table }))
+            }
+            TransError::InvalidAggregate(column) | TransError::InvalidSort(column) => {
+                Some(json!({ "column": column }))
+            }
+            TransError::RoleNotGranted(role) => Some(json!({ "role": role })),
+            TransError::WidgetNotFound(widget_id) => Some(json!({ "widget_id": widget_id })),
+            TransError::ViewNotFound(view_id) => Some(json!({ "view_id": view_id })),
+            TransError::TooManyRequests(secs) => Some(json!({ "retry_after": secs })),
+            TransError::Database(e) if retryable(e) => Some(json!({ "retryable": true })),
+            _ => None,
+        }
+    }
+
+    /// 按当前请求的语言生成错误响应体
+    pub(crate) fn body(&self) -> ErrorBody {
+        ErrorBody {
+            code: self.code(),
+            message: self.message(),
+            details: self.details(),
+            request_id: request_id::current(),
+        }
+    }
+}
+
+fn mysql_code(e: &sqlx::Error) -> Option<u16> {
+    e.as_database_error()
+        .and_then(|e| e.try_downcast_ref::<MySqlDatabaseError>())
+        .map(MySqlDatabaseError::number)
+}
+
+/// 死锁、锁等待超时和连接池耗尽可以由前端重试
+fn retryable(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::PoolTimedOut)
+        || matches!(mysql_code(e), Some(ER_LOCK_DEADLOCK | ER_LOCK_WAIT_TIMEOUT))
+}
+
+impl ResponseError for TransError {
+    fn status(&self) -> StatusCode {
+        match self {
+            TransError::RequestMustContain(_)
+            | TransError::TooManyFields(..)
+            | TransError::InvalidImportFile(_)
+            | TransError::InvalidAggregate(_)
+            | TransError::InvalidSort(_) => StatusCode::BAD_REQUEST,
+            TransError::CrudInfoNotFound(..)
+            | TransError::MenuNotFound(_)
+            | TransError::DictNotFound(_)
+            | TransError::ImportJobNotFound(_)
+            | TransError::RelationNotFound(..)
+            | TransError::WidgetNotFound(_)
+            | TransError::ViewNotFound(_) => StatusCode::NOT_FOUND,
+            TransError::UserRequired => StatusCode::UNAUTHORIZED,
+            TransError::CsrfTokenMismatch | TransError::RoleNotGranted(_) => StatusCode::FORBIDDEN,
+            TransError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            TransError::Export(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            TransError::Database(e) => match mysql_code(e) {
+                Some(ER_DUP_ENTRY) => StatusCode::CONFLICT,
+                Some(ER_ROW_IS_REFERENCED | ER_NO_REFERENCED_ROW) => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                _ if retryable(e) => StatusCode::SERVICE_UNAVAILABLE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            TransError::Http(status, _) => *status,
+        }
+    }
+
+    fn as_response(&self) -> Response {
+        let body = self.body();
+        let mut resp = Response::builder()
+            .status(self.status())
+            .content_type("application/json; charset=utf-8")
+            .body(serde_json::to_string(&body).unwrap_or_default());
+        match self {
+            TransError::TooManyRequests(secs) => {
+                resp.headers_mut().insert(RETRY_AFTER, (*secs).into());
+            }
+            TransError::Database(e) if retryable(e) => {
+                resp.headers_mut().insert(RETRY_AFTER, 1.into());
+            }
+            _ => {}
+        }
+        resp
+    }
+}
+
+/// 统一的错误响应体
+#[derive(Object, Serialize)]
+pub(crate) struct ErrorBody {
+    /// 稳定的错误码，如 DUPLICATE_KEY
+    code: String,
+    message: String,
+    details: Option<Value>,
+    request_id: Option<String>,
+}
+
+/// API 的错误类型，在 OpenAPI 文档中声明统一的错误响应
+pub(crate) struct ApiError(poem::Error);
+
+pub(crate) type ApiResult<T> = Result<T, ApiError>;
+
+impl<T: Into<TransError>> From<T> for ApiError {
+    fn from(e: T) -> Self {
+        let e = e.into();
+        match &e {
+            TransError::Database(db) => tracing::error!("数据库错误：{db}"),
+            TransError::Export(msg) => tracing::error!("导出失败：{msg}"),
+            _ => {}
+        }
+        ApiError(e.into())
+    }
+}
+
+impl From<ApiError> for poem::Error {
+    fn from(e: ApiError) -> Self {
+        e.0
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        self.0.into_response()
+    }
+}
+
+impl ApiResponse for ApiError {
+    fn meta() -> MetaResponses {
+        let responses = [
+            (400, "Invalid request"),
+            (401, "User identity required"),
+            (403, "CSRF token mismatch or role not granted"),
+            (404, "Metadata or record not found"),
+            (409, "Duplicate key"),
+            (422, "Foreign key violation"),
+            (429, "Too many requests"),
+            (500, "Internal server error"),
+            (503, "Database busy, retryable"),
+        ];
+        MetaResponses {
+            responses: responses
+                .iter()
+                .map(|&(status, description)| MetaResponse {
+                    description,
+                    status: Some(status),
+                    status_range: None,
+                    content: vec![MetaMediaType {
+                        content_type: "application/json; charset=utf-8",
+                        schema: ErrorBody::schema_ref(),
+                    }],
+                    headers: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        ErrorBody::register(registry);
+    }
+}
+
+/// 将 poem 产生的其它错误包装为统一的错误响应，保留 poem-openapi 写入的数据
+pub(crate) fn wrap(e: poem::Error) -> poem::Error {
+    if e.is::<TransError>() {
+        return e;
+    }
+    let mut wrapped = poem::Error::from(TransError::Http(e.status(), e.to_string()));
+    if let Some(operation_id) = e.data::<poem_openapi::OperationId>() {
+        wrapped.set_data(*operation_id);
+    }
+    wrapped
 }
@@ -0,0 +1,205 @@
+use std::cmp::Reverse;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use arc_swap::access::Access;
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime, UtcOffset};
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Layered, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Layer, Registry};
+
+use crate::config::{self, Config, LogCfg, GLOBAL_CONFIG};
+
+/// 日志级别的热更新句柄
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+type Filtered = Layered<reload::Layer<LevelFilter, Registry>, Registry>;
+type BoxedLayer = Box<dyn Layer<Filtered> + Send + Sync>;
+
+pub(crate) fn init_log() -> WorkerGuard {
+    //加载配置
+    config::reload();
+    let cfg = GLOBAL_CONFIG
+        .get()
+        .unwrap()
+        .map(|cfg: &Config| &cfg.log)
+        .load();
+    let cfg: &LogCfg = &cfg;
+
+    let offset = cfg.offset().expect("日志时区配置错误");
+    let (non_blocking, guard) = if cfg.max_size > 0 {
+        let writer = SizeRolling::new(cfg, offset).expect("日志文件创建失败");
+        tracing_appender::non_blocking(writer)
+    } else {
+        let mut builder = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(&*cfg.file_name);
+        if cfg.max_files > 0 {
+            builder = builder.max_log_files(cfg.max_files);
+        }
+        let appender = builder.build(&*cfg.directory).expect("日志文件创建失败");
+        tracing_appender::non_blocking(appender)
+    };
+
+    let level = cfg.level.parse::<Level>().expect("日志级别配置错误");
+    let (filter, handle) = reload::Layer::new(LevelFilter::from_level(level));
+    LEVEL_HANDLE.set(handle).ok();
+
+    let mut layers = vec![layer(cfg, offset, non_blocking, false)];
+    if cfg.stdout {
+        layers.push(layer(cfg, offset, io::stdout, !cfg.json));
+    }
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .init();
+
+    guard
+}
+
+fn layer<W>(cfg: &LogCfg, offset: UtcOffset, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
+        .with_ansi(ansi)
+        .with_thread_ids(true)
+        .with_timer(OffsetTime::new(offset, Rfc3339))
+        .with_writer(writer);
+    if cfg.json {
+        layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed()
+    } else {
+        layer.boxed()
+    }
+}
+
+/// 运行时调整日志级别
+pub(crate) fn set_level(level: Level) {
+    if let Some(handle) = LEVEL_HANDLE.get() {
+        match handle.reload(LevelFilter::from_level(level)) {
+            Ok(_) => tracing::info!("日志级别已调整为 {level}"),
+            Err(e) => tracing::error!("日志级别调整失败: {e}"),
+        }
+    }
+}
+
+/// 按天且按大小滚动的日志文件：`<file_name>.<日期>`，超过大小后改名为 `<file_name>.<日期>.<序号>`
+struct SizeRolling {
+    directory: PathBuf,
+    file_name: String,
+    max_size: u64,
+    max_files: usize,
+    offset: UtcOffset,
+    date: Date,
+    size: u64,
+    file: File,
+}
+
+impl SizeRolling {
+    fn new(cfg: &LogCfg, offset: UtcOffset) -> io::Result<Self> {
+        fs::create_dir_all(&cfg.directory)?;
+        let directory = PathBuf::from(&cfg.directory);
+        let date = OffsetDateTime::now_utc().to_offset(offset).date();
+        let path = directory.join(format!("{}.{date}", cfg.file_name));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        let rolling = SizeRolling {
+            directory,
+            file_name: cfg.file_name.clone(),
+            max_size: cfg.max_size,
+            max_files: cfg.max_files,
+            offset,
+            date,
+            size,
+            file,
+        };
+        rolling.prune();
+        Ok(rolling)
+    }
+
+    fn path(&self) -> PathBuf {
+        self.directory
+            .join(format!("{}.{}", self.file_name, self.date))
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        let today = OffsetDateTime::now_utc().to_offset(self.offset).date();
+        if today == self.date && self.size < self.max_size {
+            return Ok(());
+        }
+
+        if today == self.date {
+            let path = self.path();
+            let rolled = (1..)
+                .map(|n| {
+                    self.directory
+                        .join(format!("{}.{}.{n}", self.file_name, self.date))
+                })
+                .find(|p| !p.exists())
+                .unwrap();
+            self.file.flush()?;
+            fs::rename(path, rolled)?;
+        }
+
+        self.date = today;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())?;
+        self.size = self.file.metadata()?.len();
+        self.prune();
+        Ok(())
+    }
+
+    /// 只保留最新的 max_files 个日志文件
+    fn prune(&self) {
+        if self.max_files == 0 {
+            return;
+        }
+        let prefix = format!("{}.", self.file_name);
+        let mut files = match fs::read_dir(&self.directory) {
+            Ok(dir) => dir
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+                .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                eprintln!("读取日志目录失败: {e}");
+                return;
+            }
+        };
+        files.sort_by_key(|f| Reverse(f.0));
+        for (_, path) in files.into_iter().skip(self.max_files) {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("删除日志文件 {} 失败: {e}", path.display());
+            }
+        }
+    }
+}
+
+impl Write for SizeRolling {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.roll()?;
+        let n = self.file.write(buf)?;
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
@@ -0,0 +1,52 @@
+use poem::web::Data;
+use poem_openapi::payload::Json;
+use poem_openapi::OpenApi;
+
+use crate::error::ApiResult;
+use crate::web::api::ApiTags;
+use crate::web::meta::cache::{self, MetaStats};
+use crate::web::meta::check::{self, Inconsistency};
+use crate::web::{user, DbPool};
+
+pub(crate) struct AdminApi;
+
+/// 管理接口要求当前用户拥有的角色
+const ADMIN_ROLE: &str = "admin";
+
+#[OpenApi(prefix_path = "/admin", tag = "ApiTags::Admin")]
+impl AdminApi {
+    /// 校验 CRUD 元数据，返回全部不一致项
+    #[oai(
+        path = "/meta/check",
+        method = "get",
+        operation_id = "admin_check_meta"
+    )]
+    async fn check_meta(&self, pool: Data<&DbPool>) -> ApiResult<Json<Vec<Inconsistency>>> {
+        user::current().require_role(ADMIN_ROLE)?;
+        let report = check::check(pool.0).await?;
+        Ok(Json(report))
+    }
+
+    /// 重新加载 CRUD 元数据缓存，返回表的数量
+    #[oai(
+        path = "/meta/reload",
+        method = "post",
+        operation_id = "admin_reload_meta"
+    )]
+    async fn reload_meta(&self, pool: Data<&DbPool>) -> ApiResult<Json<u64>> {
+        user::current().require_role(ADMIN_ROLE)?;
+        let count = cache::reload(pool.0).await?;
+        Ok(Json(count as u64))
+    }
+
+    /// CRUD 元数据缓存命中统计
+    #[oai(
+        path = "/meta/stats",
+        method = "get",
+        operation_id = "admin_meta_stats"
+    )]
+    async fn meta_stats(&self) -> ApiResult<Json<MetaStats>> {
+        user::current().require_role(ADMIN_ROLE)?;
+        Ok(Json(cache::stats()))
+    }
+}
@@ -1,32 +1,43 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use poem::error::{BadRequest, InternalServerError};
 use poem::web::Data;
-use poem::Result;
-use poem_openapi::payload::Json;
-use poem_openapi::{Object, OpenApi};
+use poem::Body;
+use poem_openapi::param::{Path, Query as QueryParam};
+use poem_openapi::payload::{Attachment, AttachmentType, Json};
+use poem_openapi::types::multipart::Upload;
+use poem_openapi::{Enum, Multipart, Object, OpenApi};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use sqlx::database::HasArguments;
-use sqlx::mysql::MySqlRow;
+use sqlx::mysql::{MySqlArguments, MySqlConnection, MySqlRow};
 use sqlx::query::Query;
 use sqlx::types::time::{Date, PrimitiveDateTime, Time};
 use sqlx::types::BigDecimal;
 use sqlx::{Column, MySql, Row};
-use time::format_description;
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use tokio::sync::mpsc::{self, Receiver};
 use tokio_stream::StreamExt;
+use tracing::Instrument;
 
-use crate::error::TransError::{CrudInfoNotFound, RequestMustContain};
-use crate::web::api::ApiTags;
+use crate::error::ApiResult;
+use crate::error::TransError::{
+    self, CrudInfoNotFound, InvalidAggregate, InvalidSort, RelationNotFound, RequestMustContain,
+    TooManyFields,
+};
+use crate::web::api::dict::{self, dict_keys, DictKey};
+use crate::web::api::import::{self, ImportJob, ImportProgress, ImportReport, RowErrorKind, Sheet};
+use crate::web::api::{export, ApiTags};
+use crate::web::i18n;
+use crate::web::meta::cache::{self, ColumnMeta, Relation, TableMeta, TABLE_ALIAS};
+use crate::web::metrics;
 use crate::web::DbPool;
+use crate::GLOBAL_CONFIG;
 
 pub(crate) struct CRUDApi;
 
-const SQL_CRUD_TYPE_EQ: &str =
-    "SELECT i.`column_name`,i.`column_must`,t.`column_type` FROM CRUD_INFO i left join table_columns t on i.`table_name`=t.`table_name` and i.`column_name` = t.`column_name` WHERE i.`table_name` = ? and i.`crud_type` = ? order by i.`column_order`";
-
-const SQL_CRUD_TYPE_IN: &str =
-    "SELECT i.`crud_type`,i.`column_name`,i.`column_must`,t.`column_type` FROM CRUD_INFO i left join table_columns t on i.`table_name`=t.`table_name` and i.`column_name` = t.`column_name` WHERE i.`table_name` = ? and i.`crud_type` in (?,?) order by i.`crud_type`,i.`column_order`";
-
 // 新增
 const CRUD_C: &str = "c";
 // 查询结果
@@ -40,283 +51,1476 @@ const CRUD_V: &str = "v";
 // 删除条件
 const CRUD_D: &str = "d";
 
+// DATE、TIME、DATETIME 列的文本格式，与前端的日期时间控件一致
+const DATE_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[year]-[month]-[day]");
+const TIME_FORMAT: &[BorrowedFormatItem<'_>] = format_description!("[hour]:[minute]:[second]");
+const DATETIME_FORMAT: &[BorrowedFormatItem<'_>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+/// table_columns.COLUMN_STYLE 中的多选框
+const STYLE_CHECKBOX: i32 = 3;
+
+/// 导出时数据库读取与文件写入之间缓冲的行数
+const EXPORT_BUFFER: usize = 256;
+
+/// 表的主键和唯一键，按索引名和列序排列
+const SQL_UNIQUE_KEYS: &str = "SELECT CAST(`INDEX_NAME` AS CHAR), CAST(`COLUMN_NAME` AS CHAR) FROM information_schema.statistics WHERE `TABLE_SCHEMA` = database() AND `TABLE_NAME` = ? AND `NON_UNIQUE` = 0 ORDER BY `INDEX_NAME`, `SEQ_IN_INDEX`";
+
+/// 嵌入从表时每条语句 IN 条件中主表键的个数
+const EMBED_CHUNK: usize = 500;
+/// 嵌入从表时用于分组的外键列别名
+const PARENT_KEY: &str = "__parent_key";
+
+pub(crate) const CRUD_TYPES: [&str; 6] = [CRUD_C, CRUD_R, CRUD_S, CRUD_U, CRUD_V, CRUD_D];
+
+/// `bind_value` 与 `get_value` 支持的 table_columns.COLUMN_TYPE
+pub(crate) const COLUMN_TYPES: [&str; 17] = [
+    "BIGINT",
+    "BIGINT_UNSIGNED",
+    "BLOB",
+    "BOOLEAN",
+    "DATE",
+    "DATETIME",
+    "DECIMAL",
+    "DOUBLE",
+    "FLOAT",
+    "INT",
+    "INT_UNSIGNED",
+    "SMALLINT",
+    "SMALLINT_UNSIGNED",
+    "TEXT",
+    "TIME",
+    "TINYINT",
+    "TINYINT_UNSIGNED",
+];
+
+/// 可以求和、求平均和取最值的列类型
+const NUMERIC_TYPES: [&str; 12] = [
+    "BIGINT",
+    "BIGINT_UNSIGNED",
+    "BOOLEAN",
+    "DECIMAL",
+    "DOUBLE",
+    "FLOAT",
+    "INT",
+    "INT_UNSIGNED",
+    "SMALLINT",
+    "SMALLINT_UNSIGNED",
+    "TINYINT",
+    "TINYINT_UNSIGNED",
+];
+
+/// 导出文件格式
+#[derive(Enum, Default, Clone, Copy)]
+#[oai(rename_all = "lowercase")]
+enum ExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Object)]
+struct UpsertInfo {
+    table_name: String,
+    /// 每行为 c 列的值，冲突时其中的 u 列会被更新
+    rows: Vec<HashMap<String, String>>,
+}
+
+impl UpsertInfo {
+    fn check_limits(&self) -> ApiResult<()> {
+        let cfg = GLOBAL_CONFIG.get().unwrap().load();
+        if self.rows.len() > cfg.limit.max_rows {
+            return Err(TooManyFields("rows".to_string(), cfg.limit.max_rows).into());
+        }
+        if self.rows.iter().any(|r| r.len() > cfg.limit.max_columns) {
+            return Err(TooManyFields("columns".to_string(), cfg.limit.max_columns).into());
+        }
+        Ok(())
+    }
+}
+
+/// upsert 单行的结果
+#[derive(Enum, Clone, Copy, PartialEq, Eq, Debug)]
+#[oai(rename_all = "lowercase")]
+pub(crate) enum UpsertStatus {
+    Inserted,
+    Updated,
+    Unchanged,
+}
+
+impl UpsertStatus {
+    /// existed 为写入前是否已有键冲突的记录。sqlx 连接启用了 CLIENT_FOUND_ROWS，
+    /// 已有记录的值有变化时 rows_affected 为 2，没有变化时与新增一样为 1
+    fn new(existed: bool, rows_affected: u64) -> Self {
+        match (existed, rows_affected) {
+            (false, _) => UpsertStatus::Inserted,
+            (true, 2) => UpsertStatus::Updated,
+            (true, _) => UpsertStatus::Unchanged,
+        }
+    }
+}
+
+#[derive(Object, Default)]
+struct UpsertResult {
+    inserted: u64,
+    updated: u64,
+    unchanged: u64,
+    /// 与请求中 rows 的顺序一致
+    rows: Vec<UpsertStatus>,
+}
+
+impl UpsertResult {
+    fn push(&mut self, status: UpsertStatus) {
+        match status {
+            UpsertStatus::Inserted => self.inserted += 1,
+            UpsertStatus::Updated => self.updated += 1,
+            UpsertStatus::Unchanged => self.unchanged += 1,
+        }
+        self.rows.push(status);
+    }
+}
+
+/// 导入的上传表单
+#[derive(Multipart)]
+struct ImportRequest {
+    table_name: String,
+    /// CSV（UTF-8）或 XLSX，首行为列名或列描述
+    file: Upload,
+    /// 只校验不写入
+    dry_run: Option<bool>,
+    /// 主键或唯一键冲突时更新 u 列
+    upsert: Option<bool>,
+}
+
+/// 主表中的一条记录
+#[derive(Object)]
+struct ParentKey {
+    table_name: String,
+    /// 主表关联列的值
+    value: String,
+}
+
+/// 查询结果的排序列
+#[derive(Object, Serialize, Deserialize, Clone)]
+pub(crate) struct SortKey {
+    /// r 列
+    column: String,
+    desc: Option<bool>,
+}
+
 #[derive(Object)]
 struct CRUDInfo {
     table_name: String,
     columns: HashMap<String, String>,
     conditions: HashMap<String, String>,
+    /// 查询时为配置了字典的列及配置了标签列的外键追加 `<列名>_label` 字段
+    with_label: Option<bool>,
+    /// 查询、新增和删除限定在该主表记录下，外键值以此为准
+    parent: Option<ParentKey>,
+    /// 查询时附带这些从表的记录，字段名为从表名
+    embed: Option<Vec<String>>,
+    /// 查询和导出时按顺序排序
+    sort: Option<Vec<SortKey>>,
+    /// 查询时跳过的行数，limit 为空时忽略
+    offset: Option<u64>,
+    /// 查询时最多返回的行数，不超过 `limit.max_rows`；导出时忽略
+    limit: Option<u64>,
 }
 
-#[OpenApi(prefix_path="/crud", tag=ApiTags::Crud)]
-impl CRUDApi {
-    #[oai(path = "/create", method = "post")]
-    async fn create(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> Result<Json<u64>> {
-        let table_name = req.0.table_name;
-        let req = req.0.columns;
-        if req.is_empty() {
-            return Err(BadRequest(RequestMustContain(
-                "columns to create".to_string(),
-            )));
+impl CRUDInfo {
+    /// 限制单个请求的列数和条件数
+    fn check_limits(&self) -> ApiResult<()> {
+        let cfg = GLOBAL_CONFIG.get().unwrap().load();
+        if self.columns.len() > cfg.limit.max_columns {
+            return Err(TooManyFields("columns".to_string(), cfg.limit.max_columns).into());
+        }
+        if self.conditions.len() > cfg.limit.max_conditions {
+            return Err(TooManyFields("conditions".to_string(), cfg.limit.max_conditions).into());
+        }
+        if self.embed.as_ref().map_or(0, Vec::len) > cfg.limit.max_columns {
+            return Err(TooManyFields("embed".to_string(), cfg.limit.max_columns).into());
         }
+        if self.sort.as_ref().map_or(0, Vec::len) > cfg.limit.max_columns {
+            return Err(TooManyFields("sort".to_string(), cfg.limit.max_columns).into());
+        }
+        if self.limit.unwrap_or_default() > cfg.limit.max_rows as u64 {
+            return Err(TooManyFields("rows".to_string(), cfg.limit.max_rows).into());
+        }
+        Ok(())
+    }
+}
 
-        let mut sql = format!("INSERT INTO {}(", &*table_name);
-        let mut values_sql = String::from("(");
-        let mut placeholders = Vec::new();
-        let mut insert_stream = sqlx::query_as::<_, (String, bool, String)>(SQL_CRUD_TYPE_EQ)
-            .bind(&*table_name)
-            .bind(CRUD_C)
-            .fetch(pool.0);
-
-        while let Some(a) = insert_stream.next().await {
-            let (column_name, column_must, column_type) = a.map_err(InternalServerError)?;
-
-            if req.contains_key(&*column_name) {
-                sql += &*column_name;
-                sql += ",";
-                values_sql += "?,";
-                placeholders.push((column_name, column_type));
-            } else if column_must {
-                return Err(BadRequest(RequestMustContain(column_name.to_string())));
-            }
+/// 表作为从表和主表的关联
+#[derive(Object, Default)]
+struct RelationInfo {
+    parents: Vec<Relation>,
+    children: Vec<Relation>,
+}
+
+#[derive(Enum, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[oai(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AggregateFunc {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl AggregateFunc {
+    fn sql(self) -> &'static str {
+        match self {
+            AggregateFunc::Count => "COUNT",
+            AggregateFunc::Sum => "SUM",
+            AggregateFunc::Avg => "AVG",
+            AggregateFunc::Min => "MIN",
+            AggregateFunc::Max => "MAX",
         }
+    }
+
+    /// 图表中系列的名称，column 为列描述
+    fn label(self, column: Option<&str>) -> String {
+        let column = column.unwrap_or_default();
+        let key = match self {
+            AggregateFunc::Count if column.is_empty() => "aggregate.count",
+            AggregateFunc::Count => "aggregate.count_of",
+            AggregateFunc::Sum => "aggregate.sum",
+            AggregateFunc::Avg => "aggregate.avg",
+            AggregateFunc::Min => "aggregate.min",
+            AggregateFunc::Max => "aggregate.max",
+        };
+        i18n::tr(key, &[("column", &column)])
+    }
+}
+
+#[derive(Object, Serialize, Deserialize)]
+pub(crate) struct Aggregate {
+    func: AggregateFunc,
+    /// r 列；count 时可省略，表示统计行数
+    column: Option<String>,
+}
+
+#[derive(Object)]
+struct AggregateInfo {
+    table_name: String,
+    /// 分组的 r 列，为空时只返回一组
+    group_by: Vec<String>,
+    aggregates: Vec<Aggregate>,
+    /// 与 read 相同的 s 条件
+    conditions: HashMap<String, String>,
+    /// 分组值附带字典或主表标签
+    with_label: Option<bool>,
+    parent: Option<ParentKey>,
+}
 
-        if placeholders.is_empty() {
-            return Err(BadRequest(RequestMustContain(
-                "columns to create".to_string(),
-            )));
+impl AggregateInfo {
+    fn check_limits(&self) -> ApiResult<()> {
+        let cfg = GLOBAL_CONFIG.get().unwrap().load();
+        if self.group_by.len() + self.aggregates.len() > cfg.limit.max_columns {
+            return Err(TooManyFields("columns".to_string(), cfg.limit.max_columns).into());
         }
+        if self.conditions.len() > cfg.limit.max_conditions {
+            return Err(TooManyFields("conditions".to_string(), cfg.limit.max_conditions).into());
+        }
+        Ok(())
+    }
+}
 
-        values_sql.pop();
-        values_sql += ")";
-        sql.pop();
-        sql += ") VALUES ";
-        sql += &values_sql;
+/// 一个分组的统计结果
+#[derive(Object)]
+struct AggregateRow {
+    /// 与 group_by 的顺序一致
+    keys: Vec<Option<String>>,
+    /// with_label 时为字典或主表标签，没有标签时与 keys 相同
+    labels: Vec<Option<String>>,
+    /// 与 aggregates 的顺序一致，没有可统计的值时为空
+    values: Vec<Option<f64>>,
+}
+
+#[derive(Object, Default)]
+struct AggregateResult {
+    /// 分组列的描述，作为图表的分类
+    dimensions: Vec<String>,
+    /// 聚合值的名称，作为图表的系列
+    measures: Vec<String>,
+    rows: Vec<AggregateRow>,
+    /// 分组数超过 limit.max_groups 时只返回前 max_groups 组
+    truncated: bool,
+}
+
+/// 前端渲染表单所需的列信息
+#[derive(Object)]
+struct ColumnInfo {
+    column_name: String,
+    column_must: bool,
+    /// 1-input 2-select 3-checkbox 4-radio 5-date 6-time 7-datetime 8-textarea
+    column_style: Option<i32>,
+    column_type: Option<String>,
+    column_dict: Option<i32>,
+    column_desc: Option<String>,
+}
+
+impl From<&ColumnMeta> for ColumnInfo {
+    fn from(c: &ColumnMeta) -> Self {
+        ColumnInfo {
+            column_name: c.column_name.clone(),
+            column_must: c.column_must,
+            column_style: c.column_style,
+            column_type: Some(c.column_type.clone()).filter(|t| !t.is_empty()),
+            column_dict: c.column_dict,
+            column_desc: c.desc(i18n::current().code()).map(str::to_string),
+        }
+    }
+}
+
+#[OpenApi(prefix_path="/crud", tag=ApiTags::Crud)]
+impl CRUDApi {
+    #[oai(
+        path = "/columns/:table_name/:crud_type",
+        method = "get",
+        operation_id = "crud_columns"
+    )]
+    async fn columns(
+        &self,
+        pool: Data<&DbPool>,
+        table_name: Path<String>,
+        crud_type: Path<String>,
+    ) -> ApiResult<Json<Vec<ColumnInfo>>> {
+        let columns = match table_meta(pool.0, &table_name.0, "columns").await? {
+            Some(meta) => meta.columns(&crud_type.0).iter().map(Into::into).collect(),
+            None => Vec::new(),
+        };
+
+        Ok(Json(columns))
+    }
+
+    #[oai(
+        path = "/relations/:table_name",
+        method = "get",
+        operation_id = "crud_relations"
+    )]
+    async fn relations(
+        &self,
+        pool: Data<&DbPool>,
+        table_name: Path<String>,
+    ) -> ApiResult<Json<RelationInfo>> {
+        let relations = table_meta(pool.0, &table_name.0, "relations")
+            .await?
+            .map(|meta| RelationInfo {
+                parents: meta.parents.clone(),
+                children: meta.children.clone(),
+            })
+            .unwrap_or_default();
+
+        Ok(Json(relations))
+    }
+
+    #[oai(path = "/create", method = "post", operation_id = "crud_create")]
+    async fn create(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> ApiResult<Json<u64>> {
+        req.check_limits()?;
+        let table_name = req.0.table_name;
+        let parent = req.0.parent;
+        let mut req = req.0.columns;
+        if req.is_empty() {
+            return Err(RequestMustContain("columns to create".to_string()).into());
+        }
+
+        let meta = table_meta(pool.0, &table_name, "create")
+            .await?
+            .unwrap_or_default();
+        let scope = parent_scope(&meta, &table_name, parent, &mut req)?;
+        let (sql, placeholders) = insert_statement(&meta, &req, false, scope.as_ref())?;
         let mut query = sqlx::query(&sql);
         query = query_bind_value(query, req, placeholders);
-        let res = query.execute(pool.0).await.map_err(InternalServerError)?;
+        let res = query.execute(pool.0).await?;
+        cache::reload_if_meta(pool.0, &table_name).await;
+        dict::invalidate_if_dict(&table_name);
 
         Ok(Json(res.rows_affected()))
     }
 
-    #[oai(path = "/read", method = "post")]
-    async fn read(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> Result<Json<Value>> {
-        let table_name = req.0.table_name;
-        let req = req.0.conditions;
+    /// 按表头导入 CSV/XLSX 到 c 列，dry_run 时只返回校验报告；
+    /// 全部行校验通过才会在后台事务中写入，通过 job_id 查询进度
+    #[oai(path = "/import", method = "post", operation_id = "crud_import")]
+    async fn import(
+        &self,
+        pool: Data<&DbPool>,
+        req: ImportRequest,
+    ) -> ApiResult<Json<ImportReport>> {
+        let meta = match table_meta(pool.0, &req.table_name, "import").await? {
+            Some(meta) if !meta.columns(CRUD_C).is_empty() => meta,
+            _ => return Err(CrudInfoNotFound(req.table_name, String::from(CRUD_C)).into()),
+        };
 
-        let mut sql = String::from("SELECT ");
-        let mut select_columns = HashMap::new();
-        let mut placeholders = Vec::new();
-        let mut last_crud_type = "";
-        let mut select_stream =
-            sqlx::query_as::<_, (String, String, bool, String)>(SQL_CRUD_TYPE_IN)
-                .bind(&*table_name)
-                .bind(CRUD_R)
-                .bind(CRUD_S)
-                .fetch(pool.0);
-
-        while let Some(res) = select_stream.next().await {
-            let (crud_type, column_name, column_must, column_type) =
-                res.map_err(InternalServerError)?;
-
-            if last_crud_type.is_empty() && crud_type != CRUD_R {
-                return Err(BadRequest(CrudInfoNotFound(
-                    table_name.to_string(),
-                    String::from(CRUD_R),
-                )));
-            }
+        let max_rows = GLOBAL_CONFIG.get().unwrap().load().limit.max_import_rows;
+        let sheet = import::read(req.file, max_rows).await?;
+        let (rows, mut report) = validate_import(pool.0, &meta, sheet).await?;
+        if req.dry_run.unwrap_or_default() || report.invalid > 0 || rows.is_empty() {
+            return Ok(Json(report));
+        }
 
-            if crud_type == CRUD_R {
-                select_columns.insert(column_name.clone(), column_type);
-                sql += &column_name;
-                sql += ",";
-                last_crud_type = CRUD_R;
-            } else if crud_type == CRUD_S {
-                if last_crud_type == CRUD_R {
-                    sql.pop();
-                    sql += " FROM ";
-                    sql += &*table_name;
-                    sql += " WHERE ";
-                    last_crud_type = CRUD_S;
-                }
+        let job = ImportJob::start(rows.len() as u64);
+        report.job_id = Some(job.id().to_string());
+        let upsert = req.upsert.unwrap_or_default();
+        tokio::spawn(
+            import_rows(pool.0.clone(), req.table_name, meta, rows, upsert, job).in_current_span(),
+        );
 
-                if req.contains_key(&column_name) {
-                    sql += &column_name;
-                    sql += " = ? AND ";
-                    placeholders.push((column_name, column_type));
-                } else if column_must {
-                    return Err(BadRequest(RequestMustContain(table_name.to_string())));
-                }
-            }
-        }
+        Ok(Json(report))
+    }
 
-        if select_columns.is_empty() {
-            return Err(BadRequest(RequestMustContain(
-                "columns to read".to_string(),
-            )));
+    #[oai(
+        path = "/import/:job_id",
+        method = "get",
+        operation_id = "crud_import_progress"
+    )]
+    async fn import_progress(&self, job_id: Path<String>) -> ApiResult<Json<ImportProgress>> {
+        Ok(Json(import::progress(&job_id.0)?))
+    }
+
+    /// 批量新增或更新：主键或唯一键冲突时更新 u 列，全部行在一个事务中执行
+    #[oai(path = "/upsert", method = "post", operation_id = "crud_upsert")]
+    async fn upsert(
+        &self,
+        pool: Data<&DbPool>,
+        req: Json<UpsertInfo>,
+    ) -> ApiResult<Json<UpsertResult>> {
+        req.check_limits()?;
+        let table_name = req.0.table_name;
+        let rows = req.0.rows;
+        if rows.is_empty() {
+            return Err(RequestMustContain("rows to upsert".to_string()).into());
         }
 
-        if last_crud_type == CRUD_R {
-            sql.pop();
-            sql += " FROM ";
-            sql += &*table_name;
+        let meta = match table_meta(pool.0, &table_name, "upsert").await? {
+            Some(meta) if !meta.columns(CRUD_C).is_empty() => meta,
+            _ => return Err(CrudInfoNotFound(table_name, String::from(CRUD_C)).into()),
+        };
+
+        let mut result = UpsertResult::default();
+        let mut tx = pool.0.begin().await?;
+        let keys = unique_keys(&mut tx, &table_name).await?;
+        for row in rows {
+            result.push(write_row(&mut tx, &meta, &keys, row, true).await?);
         }
+        tx.commit().await?;
+        cache::reload_if_meta(pool.0, &table_name).await;
+        dict::invalidate_if_dict(&table_name);
 
-        sql = sql
-            .trim_end_matches("WHERE ")
-            .trim_end_matches("AND ")
-            .to_string();
+        Ok(Json(result))
+    }
+
+    #[oai(path = "/read", method = "post", operation_id = "crud_read")]
+    async fn read(&self, pool: Data<&DbPool>, mut req: Json<CRUDInfo>) -> ApiResult<Json<Value>> {
+        req.check_limits()?;
+        let table_name = req.table_name.clone();
+        let embed = req.embed.take().unwrap_or_default();
+        let with_label = req.with_label.unwrap_or_default();
+        let read = read_query(pool.0, req.0, "read").await?;
 
         let mut rows = Vec::new();
-        let mut query = sqlx::query(&sql);
-        query = query_bind_value(query, req, placeholders);
-        let mut stream = query.fetch(pool.0);
+        let mut stream = read.query().fetch(pool.0);
         while let Some(res) = stream.next().await {
-            let row = res.map_err(InternalServerError)?;
-            let mut r = serde_json::Map::new();
+            let row = res?;
+            rows.push(row_to_json(&row, &read.meta, &read.dicts));
+        }
+        drop(stream);
 
-            row.columns().iter().enumerate().for_each(|(i, col)| {
-                let column_type = select_columns.get(col.name()).unwrap();
-                r.insert(col.name().to_string(), get_value(&row, i, column_type));
-            });
+        for child_table in &embed {
+            let relation = read
+                .meta
+                .child(child_table)
+                .ok_or_else(|| RelationNotFound(child_table.clone(), table_name.clone()))?;
+            embed_children(pool.0, relation, with_label, &mut rows).await?;
+        }
+
+        Ok(Json(Value::Array(
+            rows.into_iter().map(Value::Object).collect(),
+        )))
+    }
 
-            rows.push(Value::Object(r));
+    /// 按 read 的条件统计行数，用于分页
+    #[oai(path = "/count", method = "post", operation_id = "crud_count")]
+    async fn count(&self, pool: Data<&DbPool>, mut req: Json<CRUDInfo>) -> ApiResult<Json<u64>> {
+        req.check_limits()?;
+        req.with_label = None;
+        req.sort = None;
+        req.limit = None;
+        let mut read = read_query(pool.0, req.0, "count").await?;
+        read.sql = format!("SELECT COUNT(*) FROM ({}) t", read.sql);
+
+        let count = read.query().fetch_one(pool.0).await?.try_get::<i64, _>(0)?;
+        Ok(Json(count as u64))
+    }
+
+    /// 按 read 的条件导出全部结果，表头使用列描述，字典列导出标签
+    #[oai(path = "/export", method = "post", operation_id = "crud_export")]
+    async fn export(
+        &self,
+        pool: Data<&DbPool>,
+        format: QueryParam<Option<ExportFormat>>,
+        mut req: Json<CRUDInfo>,
+    ) -> ApiResult<Attachment<Body>> {
+        req.check_limits()?;
+        req.with_label = Some(true);
+        req.limit = None;
+        let table_name = req.table_name.clone();
+        let read = read_query(pool.0, req.0, "export").await?;
+
+        let locale = i18n::current().code();
+        let headers = read
+            .meta
+            .columns(CRUD_R)
+            .iter()
+            .map(|c| c.desc(locale).unwrap_or(&c.column_name).to_string())
+            .collect();
+        let records = spawn_records(pool.0.clone(), read);
+
+        let format = format.0.unwrap_or_default();
+        let body = match format {
+            ExportFormat::Csv => export::csv(headers, records),
+            ExportFormat::Xlsx => export::xlsx(headers, records).await?,
+        };
+        Ok(Attachment::new(body)
+            .attachment_type(AttachmentType::Attachment)
+            .filename(format!("{table_name}.{}", format.extension())))
+    }
+
+    /// 按 group_by 分组统计，条件与 read 相同，只能对 r 列分组和聚合
+    #[oai(path = "/aggregate", method = "post", operation_id = "crud_aggregate")]
+    async fn aggregate(
+        &self,
+        pool: Data<&DbPool>,
+        req: Json<AggregateInfo>,
+    ) -> ApiResult<Json<AggregateResult>> {
+        req.check_limits()?;
+        let req = req.0;
+        if req.aggregates.is_empty() {
+            return Err(RequestMustContain("aggregates".to_string()).into());
+        }
+        let meta = match table_meta(pool.0, &req.table_name, "aggregate").await? {
+            Some(meta) if !meta.select_sql.is_empty() => meta,
+            _ => return Err(CrudInfoNotFound(req.table_name, String::from(CRUD_R)).into()),
+        };
+
+        let groups = group_columns(&meta, &req.group_by)?;
+        let with_label = req.with_label.unwrap_or_default();
+        let locale = i18n::current().code();
+        let mut result = AggregateResult {
+            dimensions: groups
+                .iter()
+                .map(|c| c.desc(locale).unwrap_or(&c.column_name).to_string())
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut select = groups
+            .iter()
+            .map(|c| format!("CAST({TABLE_ALIAS}.{} AS CHAR)", c.column_name))
+            .collect::<Vec<_>>();
+        if with_label {
+            // 主表标签不在 group by 中，取 MAX 以兼容 ONLY_FULL_GROUP_BY
+            select.extend(
+                groups
+                    .iter()
+                    .map(|c| match meta.label_expr(&c.column_name) {
+                        Some(expr) => format!("MAX({expr})"),
+                        None => "CAST(NULL AS CHAR)".to_string(),
+                    }),
+            );
+        }
+        for aggregate in &req.aggregates {
+            select.push(aggregate_expr(&meta, aggregate)?);
+            let column = aggregate
+                .column
+                .as_deref()
+                .and_then(|name| meta.column(CRUD_R, name))
+                .map(|c| c.desc(locale).unwrap_or(&c.column_name));
+            result.measures.push(aggregate.func.label(column));
+        }
+
+        let from = if with_label {
+            &meta.join_sql
+        } else {
+            &meta.from_sql
+        };
+        let mut sql = format!("SELECT {}{from}", select.join(","));
+        let mut conditions = req.conditions;
+        let placeholders = read_where(
+            &meta,
+            &req.table_name,
+            req.parent,
+            &mut conditions,
+            &mut sql,
+        )?;
+        if !groups.is_empty() {
+            let keys = groups
+                .iter()
+                .map(|c| format!("{TABLE_ALIAS}.{}", c.column_name))
+                .collect::<Vec<_>>()
+                .join(",");
+            sql += &format!(" GROUP BY {keys} ORDER BY {keys}");
         }
+        let max_groups = GLOBAL_CONFIG.get().unwrap().load().limit.max_groups;
+        sql += &format!(" LIMIT {}", max_groups + 1);
 
-        Ok(Json(Value::Array(rows)))
+        let dicts = if with_label {
+            column_dicts(pool.0, meta.columns(CRUD_R)).await?
+        } else {
+            HashMap::new()
+        };
+        let offset = if with_label {
+            groups.len() * 2
+        } else {
+            groups.len()
+        };
+        let mut stream =
+            query_bind_value(sqlx::query(&sql), conditions, placeholders).fetch(pool.0);
+        while let Some(res) = stream.next().await {
+            let row = res?;
+            if result.rows.len() == max_groups {
+                result.truncated = true;
+                break;
+            }
+            let keys = (0..groups.len())
+                .map(|i| row.try_get::<Option<String>, usize>(i).unwrap_or_default())
+                .collect::<Vec<_>>();
+            let labels = if with_label {
+                groups
+                    .iter()
+                    .zip(&keys)
+                    .enumerate()
+                    .map(|(i, (column, key))| {
+                        let joined = row
+                            .try_get::<Option<String>, usize>(groups.len() + i)
+                            .unwrap_or_default();
+                        joined
+                            .or_else(|| {
+                                let dict = column.column_dict.and_then(|d| dicts.get(&d))?;
+                                let key = key.as_ref()?;
+                                dict.iter().find(|k| &k.key == key).map(|k| k.value.clone())
+                            })
+                            .or_else(|| key.clone())
+                    })
+                    .collect()
+            } else {
+                keys.clone()
+            };
+            let values = (0..req.aggregates.len())
+                .map(|i| {
+                    row.try_get::<Option<BigDecimal>, usize>(offset + i)
+                        .unwrap_or_default()
+                        .and_then(|v| v.to_string().parse::<f64>().ok())
+                })
+                .collect();
+            result.rows.push(AggregateRow {
+                keys,
+                labels,
+                values,
+            });
+        }
+
+        Ok(Json(result))
     }
 
-    #[oai(path = "/update", method = "put")]
-    async fn update(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> Result<Json<u64>> {
+    #[oai(path = "/update", method = "put", operation_id = "crud_update")]
+    async fn update(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> ApiResult<Json<u64>> {
+        req.check_limits()?;
         let table_name = req.0.table_name;
         let update_columns = req.0.columns;
         let conditions = req.0.conditions;
         if update_columns.is_empty() {
-            return Err(BadRequest(RequestMustContain(
-                "columns to update".to_string(),
-            )));
+            return Err(RequestMustContain("columns to update".to_string()).into());
         }
 
-        let mut sql = format!("UPDATE {} SET ", &*table_name);
+        let meta = table_meta(pool.0, &table_name, "update")
+            .await?
+            .unwrap_or_default();
+        if meta.columns(CRUD_U).is_empty() {
+            return Err(CrudInfoNotFound(table_name, String::from(CRUD_U)).into());
+        }
+
+        let mut sql = meta.update_sql.clone();
         let mut update_placeholders = Vec::new();
         let mut where_placeholders = Vec::new();
-        let mut last_crud_type = "";
-        let mut update_stream =
-            sqlx::query_as::<_, (String, String, bool, String)>(SQL_CRUD_TYPE_IN)
-                .bind(&*table_name)
-                .bind(CRUD_U)
-                .bind(CRUD_V)
-                .fetch(pool.0);
-
-        while let Some(res) = update_stream.next().await {
-            let (crud_type, column_name, column_must, column_type) =
-                res.map_err(InternalServerError)?;
-
-            if last_crud_type.is_empty() && crud_type != CRUD_U {
-                return Err(BadRequest(CrudInfoNotFound(
-                    table_name.to_string(),
-                    String::from(CRUD_U),
-                )));
-            }
 
-            if crud_type == CRUD_U {
-                if update_columns.contains_key(&column_name) {
-                    sql += &column_name;
-                    sql += " = ?,";
-                    last_crud_type = CRUD_U;
-                    update_placeholders.push((column_name, column_type));
-                } else if column_must {
-                    return Err(BadRequest(RequestMustContain(column_name.to_string())));
-                }
-            } else if crud_type == CRUD_V {
-                if last_crud_type == CRUD_U {
-                    sql.pop();
-                    sql += " WHERE ";
-                    last_crud_type = CRUD_V;
-                } else if last_crud_type.is_empty() {
-                    return Err(BadRequest(CrudInfoNotFound(
-                        table_name.to_string(),
-                        String::from(CRUD_U),
-                    )));
-                }
-
-                if conditions.is_empty() {
-                    break;
-                }
-
-                if conditions.contains_key(&column_name) {
-                    sql += &column_name;
-                    sql += " = ? AND ";
-                    where_placeholders.push((column_name, column_type));
-                } else if column_must {
-                    return Err(BadRequest(RequestMustContain(column_name.to_string())));
-                }
+        for column in meta.columns(CRUD_U) {
+            if update_columns.contains_key(&column.column_name) {
+                sql += &column.column_name;
+                sql += " = ?,";
+                update_placeholders.push((column.column_name.clone(), column.column_type.clone()));
+            } else if column.column_must {
+                return Err(RequestMustContain(column.column_name.clone()).into());
             }
         }
 
         if update_placeholders.is_empty() {
-            return Err(BadRequest(RequestMustContain(
-                "columns to update".to_string(),
-            )));
+            return Err(RequestMustContain("columns to update".to_string()).into());
         }
+        sql.pop();
 
-        if last_crud_type == CRUD_U {
-            sql.pop();
+        if !conditions.is_empty() {
+            where_clause(
+                meta.columns(CRUD_V),
+                &conditions,
+                &mut sql,
+                &mut where_placeholders,
+                "",
+            )?;
         }
-        sql = sql
-            .trim_end_matches("WHERE ")
-            .trim_end_matches("AND ")
-            .to_string();
 
         let mut query = sqlx::query(&sql);
         query = query_bind_value(query, update_columns, update_placeholders);
         query = query_bind_value(query, conditions, where_placeholders);
 
-        let res = query.execute(pool.0).await.map_err(InternalServerError)?;
+        let res = query.execute(pool.0).await?;
+        cache::reload_if_meta(pool.0, &table_name).await;
+        dict::invalidate_if_dict(&table_name);
 
         Ok(Json(res.rows_affected()))
     }
 
-    #[oai(path = "/delete", method = "delete")]
-    async fn delete(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> Result<Json<u64>> {
+    #[oai(path = "/delete", method = "delete", operation_id = "crud_delete")]
+    async fn delete(&self, pool: Data<&DbPool>, req: Json<CRUDInfo>) -> ApiResult<Json<u64>> {
+        req.check_limits()?;
         let table_name = req.0.table_name;
-        let req = req.0.conditions;
-        let mut sql = format!("DELETE FROM {} WHERE ", &*table_name);
+        let parent = req.0.parent;
+        let mut req = req.0.conditions;
+        let meta = table_meta(pool.0, &table_name, "delete")
+            .await?
+            .unwrap_or_default();
+        let scope = parent_scope(&meta, &table_name, parent, &mut req)?;
+        let mut sql = meta.delete_sql.clone();
         let mut placeholders = Vec::new();
-        let mut delete_stream = sqlx::query_as::<_, (String, bool, String)>(SQL_CRUD_TYPE_EQ)
-            .bind(&*table_name)
-            .bind(CRUD_D)
-            .fetch(pool.0);
-
-        while let Some(a) = delete_stream.next().await {
-            let (column_name, column_must, column_type) = a.map_err(InternalServerError)?;
-
-            if req.contains_key(&*column_name) {
-                sql += &*column_name;
-                sql += " = ? AND ";
-                placeholders.push((column_name, column_type));
-            } else if column_must {
-                return Err(BadRequest(RequestMustContain(column_name.to_string())));
+        where_clause(meta.columns(CRUD_D), &req, &mut sql, &mut placeholders, "")?;
+        scope_clause(scope, "", &mut sql, &mut placeholders);
+
+        let mut query = sqlx::query(&sql);
+        query = query_bind_value(query, req, placeholders);
+        let res = query.execute(pool.0).await?;
+        cache::reload_if_meta(pool.0, &table_name).await;
+        dict::invalidate_if_dict(&table_name);
+
+        Ok(Json(res.rows_affected()))
+    }
+}
+
+/// 读取表的元数据，并记录该表的 CRUD 操作次数
+async fn table_meta(
+    pool: &DbPool,
+    table_name: &str,
+    operation: &str,
+) -> ApiResult<Option<Arc<TableMeta>>> {
+    let meta = cache::table(pool, table_name).await?;
+    if meta.is_some() {
+        metrics::crud_operation(table_name, operation);
+    }
+    Ok(meta)
+}
+
+/// 从表操作限定在 parent 记录下：把外键值写入 values，返回外键的占位符
+fn parent_scope(
+    meta: &TableMeta,
+    table_name: &str,
+    parent: Option<ParentKey>,
+    values: &mut HashMap<String, String>,
+) -> ApiResult<Option<(String, String)>> {
+    let parent = match parent {
+        Some(parent) => parent,
+        None => return Ok(None),
+    };
+    let relation = meta
+        .parent(&parent.table_name)
+        .ok_or_else(|| RelationNotFound(table_name.to_string(), parent.table_name.clone()))?;
+    let column_type = meta.column_type(&relation.column_name).unwrap_or("TEXT");
+    values.insert(relation.column_name.clone(), parent.value);
+
+    Ok(Some((
+        relation.column_name.clone(),
+        column_type.to_string(),
+    )))
+}
+
+/// where 子句中还没有外键条件时追加，外键不必配置为条件列
+fn scope_clause(
+    scope: Option<(String, String)>,
+    prefix: &str,
+    sql: &mut String,
+    placeholders: &mut Vec<(String, String)>,
+) {
+    let scope = match scope {
+        Some(scope) if placeholders.iter().all(|(name, _)| *name != scope.0) => scope,
+        _ => return,
+    };
+    *sql += if placeholders.is_empty() {
+        " WHERE "
+    } else {
+        " AND "
+    };
+    *sql += prefix;
+    *sql += &scope.0;
+    *sql += " = ?";
+    placeholders.push(scope);
+}
+
+/// 按 c 列拼接 insert 语句；upsert 时主键或唯一键冲突则改为更新 row 中的 u 列。
+/// scope 为主表外键，不是 c 列时也会写入
+fn insert_statement(
+    meta: &TableMeta,
+    row: &HashMap<String, String>,
+    upsert: bool,
+    scope: Option<&(String, String)>,
+) -> Result<(String, Vec<(String, String)>), TransError> {
+    let mut sql = meta.insert_sql.clone();
+    let mut values_sql = String::from("(");
+    let mut placeholders = Vec::new();
+
+    for column in meta.columns(CRUD_C) {
+        if scope.is_some_and(|(name, _)| *name == column.column_name) {
+            continue;
+        }
+        if row.contains_key(&*column.column_name) {
+            sql += &*column.column_name;
+            sql += ",";
+            values_sql += "?,";
+            placeholders.push((column.column_name.clone(), column.column_type.clone()));
+        } else if column.column_must {
+            return Err(RequestMustContain(column.column_name.clone()));
+        }
+    }
+    if let Some(scope) = scope {
+        sql += &scope.0;
+        sql += ",";
+        values_sql += "?,";
+        placeholders.push(scope.clone());
+    }
+
+    if placeholders.is_empty() {
+        return Err(RequestMustContain("columns to create".to_string()));
+    }
+
+    values_sql.pop();
+    values_sql += ")";
+    sql.pop();
+    sql += ") VALUES ";
+    sql += &values_sql;
+
+    if upsert {
+        let updates = meta
+            .columns(CRUD_U)
+            .iter()
+            .filter(|c| placeholders.iter().any(|(name, _)| *name == c.column_name))
+            .map(|c| format!("{0} = VALUES({0})", c.column_name))
+            .collect::<Vec<_>>();
+        sql += " ON DUPLICATE KEY UPDATE ";
+        if updates.is_empty() {
+            // 没有可更新的列时保留原有记录
+            sql += &format!("{0} = {0}", placeholders[0].0);
+        } else {
+            sql += &updates.join(",");
+        }
+    }
+
+    Ok((sql, placeholders))
+}
+
+/// 表的主键和唯一键，每个键为按顺序排列的列名
+async fn unique_keys(
+    conn: &mut MySqlConnection,
+    table_name: &str,
+) -> Result<Vec<Vec<String>>, TransError> {
+    let rows: Vec<(String, String)> = sqlx::query_as(SQL_UNIQUE_KEYS)
+        .bind(table_name)
+        .fetch_all(conn)
+        .await?;
+    let mut keys: Vec<(String, Vec<String>)> = Vec::new();
+    for (index_name, column_name) in rows {
+        match keys.last_mut() {
+            Some((name, columns)) if *name == index_name => columns.push(column_name),
+            _ => keys.push((index_name, vec![column_name])),
+        }
+    }
+    Ok(keys.into_iter().map(|(_, columns)| columns).collect())
+}
+
+/// 按行中包含全部列的键加锁查询已有记录，没有这样的键时该行不会发生键冲突
+fn existing_statement(
+    meta: &TableMeta,
+    keys: &[Vec<String>],
+    placeholders: &[(String, String)],
+) -> Option<(String, Vec<(String, String)>)> {
+    let mut clauses = Vec::new();
+    let mut binds = Vec::new();
+    for key in keys {
+        let columns = key
+            .iter()
+            .map(|k| {
+                placeholders
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(k))
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(columns) = columns {
+            let clause = columns
+                .iter()
+                .map(|(name, _)| format!("{name} = ?"))
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            clauses.push(format!("({clause})"));
+            binds.extend(columns.into_iter().cloned());
+        }
+    }
+
+    (!clauses.is_empty()).then(|| {
+        let sql = format!(
+            "SELECT 1{} WHERE {} LIMIT 1 FOR UPDATE",
+            meta.from_sql,
+            clauses.join(" OR ")
+        );
+        (sql, binds)
+    })
+}
+
+/// 写入一行；upsert 时先在同一事务中锁定已有记录，以区分新增、更新和未变化
+async fn write_row(
+    conn: &mut MySqlConnection,
+    meta: &TableMeta,
+    keys: &[Vec<String>],
+    row: HashMap<String, String>,
+    upsert: bool,
+) -> Result<UpsertStatus, TransError> {
+    let (sql, placeholders) = insert_statement(meta, &row, upsert, None)?;
+    let mut existed = false;
+    if let Some((select, binds)) = upsert
+        .then(|| existing_statement(meta, keys, &placeholders))
+        .flatten()
+    {
+        let mut query = sqlx::query(&select);
+        for (column_name, column_type) in binds {
+            if let Some(value) = row.get(&column_name) {
+                query = bind_value(query, value.clone(), column_type);
+            }
+        }
+        existed = query.fetch_optional(&mut *conn).await?.is_some();
+    }
+
+    let res = query_bind_value(sqlx::query(&sql), row, placeholders)
+        .execute(&mut *conn)
+        .await?;
+    Ok(UpsertStatus::new(existed, res.rows_affected()))
+}
+
+/// 把表头映射到 c 列并逐行校验，返回校验通过的行及报告
+async fn validate_import(
+    pool: &DbPool,
+    meta: &TableMeta,
+    sheet: Sheet,
+) -> ApiResult<(Vec<HashMap<String, String>>, ImportReport)> {
+    let columns = meta.columns(CRUD_C);
+    let mapping = sheet
+        .headers
+        .iter()
+        .map(|h| columns.iter().find(|c| c.matches(h)))
+        .collect::<Vec<_>>();
+    if mapping.iter().all(Option::is_none) {
+        return Err(RequestMustContain("columns to create".to_string()).into());
+    }
+
+    let mut report = ImportReport::default();
+    report.unmapped = sheet
+        .headers
+        .iter()
+        .zip(&mapping)
+        .filter(|(h, c)| c.is_none() && !h.is_empty())
+        .map(|(h, _)| h.clone())
+        .collect();
+
+    let dicts = column_dicts(pool, columns).await?;
+
+    let locale = i18n::current().code();
+    let label = |c: &ColumnMeta| c.desc(locale).unwrap_or(&c.column_name).to_string();
+    let mut rows = Vec::new();
+    for (line, values) in sheet.rows {
+        if values.iter().all(String::is_empty) {
+            continue;
+        }
+        report.total += 1;
+
+        let mut row = HashMap::new();
+        let mut failed = HashSet::new();
+        for (column, value) in mapping.iter().zip(values) {
+            let column = match column {
+                Some(column) if !value.is_empty() => column,
+                _ => continue,
+            };
+            match import_value(column, value, &dicts) {
+                Ok(value) => {
+                    row.insert(column.column_name.clone(), value);
+                }
+                Err(kind) => {
+                    failed.insert(&*column.column_name);
+                    report.error(line, &column.column_name, &label(column), kind);
+                }
+            }
+        }
+        for column in columns.iter().filter(|c| c.column_must) {
+            let name = &*column.column_name;
+            if !row.contains_key(name) && failed.insert(name) {
+                report.error(line, name, &label(column), RowErrorKind::Required);
+            }
+        }
+
+        if failed.is_empty() {
+            report.valid += 1;
+            rows.push(row);
+        } else {
+            report.invalid += 1;
+        }
+    }
+
+    Ok((rows, report))
+}
+
+/// 字典列可以填 KEY 或标签，多选框以逗号分隔多个值
+fn import_value(
+    column: &ColumnMeta,
+    value: String,
+    dicts: &HashMap<i32, Arc<Vec<DictKey>>>,
+) -> Result<String, RowErrorKind> {
+    let value = match column.column_dict.and_then(|d| dicts.get(&d)) {
+        Some(keys) if !keys.is_empty() => {
+            let parts = if column.column_style == Some(STYLE_CHECKBOX) {
+                value.split(',').map(str::trim).collect::<Vec<_>>()
+            } else {
+                vec![value.as_str()]
+            };
+            let mut mapped = Vec::new();
+            for part in parts {
+                match keys.iter().find(|k| k.key == part || k.value == part) {
+                    Some(k) => mapped.push(k.key.as_str()),
+                    None => return Err(RowErrorKind::NotInDict),
+                }
             }
+            mapped.join(",")
+        }
+        _ => value,
+    };
+
+    if check_value(&value, &column.column_type) {
+        Ok(value)
+    } else {
+        Err(RowErrorKind::InvalidValue(column.column_type.clone()))
+    }
+}
+
+/// 在一个事务中写入导入的行，任一行失败则全部回滚
+async fn import_rows(
+    pool: DbPool,
+    table_name: String,
+    meta: Arc<TableMeta>,
+    rows: Vec<HashMap<String, String>>,
+    upsert: bool,
+    job: Arc<ImportJob>,
+) {
+    let res = async {
+        let mut tx = pool.begin().await?;
+        let keys = if upsert {
+            unique_keys(&mut tx, &table_name).await?
+        } else {
+            Vec::new()
+        };
+        for row in rows {
+            job.row_done(write_row(&mut tx, &meta, &keys, row, upsert).await?);
+        }
+        tx.commit().await?;
+        Ok::<_, TransError>(())
+    }
+    .await;
+
+    match res {
+        Ok(()) => {
+            tracing::info!("导入{table_name}完成，任务{}", job.id());
+            job.finish(None);
+            cache::reload_if_meta(&pool, &table_name).await;
+            dict::invalidate_if_dict(&table_name);
         }
+        Err(e) => {
+            tracing::error!("导入{table_name}失败，任务{}：{e}", job.id());
+            job.finish(Some(e));
+        }
+    }
+}
 
-        sql = sql
-            .trim_end_matches("WHERE ")
-            .trim_end_matches("AND ")
-            .to_string();
+/// 查询语句及绑定的条件，read 与 export 共用
+struct ReadQuery {
+    meta: Arc<TableMeta>,
+    sql: String,
+    conditions: HashMap<String, String>,
+    placeholders: Vec<(String, String)>,
+    /// with_label 时 r 列用到的字典
+    dicts: HashMap<i32, Arc<Vec<DictKey>>>,
+}
 
+impl ReadQuery {
+    fn query(&self) -> Query<'_, MySql, MySqlArguments> {
+        query_bind_value(
+            sqlx::query(&self.sql),
+            self.conditions.clone(),
+            self.placeholders.clone(),
+        )
+    }
+}
+
+/// 按 r 列和 s 条件拼接查询语句，with_label 时 join 主表标签并预先加载字典
+async fn read_query(pool: &DbPool, req: CRUDInfo, operation: &str) -> ApiResult<ReadQuery> {
+    let meta = match table_meta(pool, &req.table_name, operation).await? {
+        Some(meta) if !meta.select_sql.is_empty() => meta,
+        _ => return Err(CrudInfoNotFound(req.table_name, String::from(CRUD_R)).into()),
+    };
+
+    let with_label = req.with_label.unwrap_or_default();
+    let mut sql = if with_label {
+        meta.label_sql.clone()
+    } else {
+        meta.select_sql.clone()
+    };
+    let mut conditions = req.conditions;
+    let placeholders = read_where(
+        &meta,
+        &req.table_name,
+        req.parent,
+        &mut conditions,
+        &mut sql,
+    )?;
+    sort_clause(&meta, &req.sort.unwrap_or_default(), &mut sql)?;
+    if let Some(limit) = req.limit {
+        sql += &format!(" LIMIT {limit} OFFSET {}", req.offset.unwrap_or_default());
+    }
+
+    let dicts = if with_label {
+        column_dicts(pool, meta.columns(CRUD_R)).await?
+    } else {
+        HashMap::new()
+    };
+
+    Ok(ReadQuery {
+        meta,
+        sql,
+        conditions,
+        placeholders,
+        dicts,
+    })
+}
+
+/// 按 s 列拼接 where 子句，parent 不为空时限定在主表记录下；read、export 与 aggregate 共用
+fn read_where(
+    meta: &TableMeta,
+    table_name: &str,
+    parent: Option<ParentKey>,
+    conditions: &mut HashMap<String, String>,
+    sql: &mut String,
+) -> ApiResult<Vec<(String, String)>> {
+    let scope = parent_scope(meta, table_name, parent, conditions)?;
+    let prefix = format!("{TABLE_ALIAS}.");
+    let mut placeholders = Vec::new();
+    where_clause(
+        meta.columns(CRUD_S),
+        conditions,
+        sql,
+        &mut placeholders,
+        &prefix,
+    )?;
+    scope_clause(scope, &prefix, sql, &mut placeholders);
+    Ok(placeholders)
+}
+
+/// 按 r 列拼接 order by 子句
+pub(crate) fn sort_clause(
+    meta: &TableMeta,
+    sort: &[SortKey],
+    sql: &mut String,
+) -> Result<(), TransError> {
+    let mut keys = Vec::with_capacity(sort.len());
+    for key in sort {
+        if meta.column(CRUD_R, &key.column).is_none() {
+            return Err(InvalidSort(key.column.clone()));
+        }
+        let direction = if key.desc.unwrap_or_default() {
+            " DESC"
+        } else {
+            ""
+        };
+        keys.push(format!("{TABLE_ALIAS}.{}{direction}", key.column));
+    }
+    if !keys.is_empty() {
+        *sql += " ORDER BY ";
+        *sql += &keys.join(",");
+    }
+    Ok(())
+}
+
+/// 分组列，只能是 r 列且不能是 BLOB
+pub(crate) fn group_columns<'a>(
+    meta: &'a TableMeta,
+    group_by: &[String],
+) -> Result<Vec<&'a ColumnMeta>, TransError> {
+    group_by
+        .iter()
+        .map(|name| match meta.column(CRUD_R, name) {
+            Some(column) if column.column_type != "BLOB" => Ok(column),
+            _ => Err(InvalidAggregate(name.clone())),
+        })
+        .collect()
+}
+
+/// 聚合表达式，sum、avg、min、max 只能用于数值列
+pub(crate) fn aggregate_expr(
+    meta: &TableMeta,
+    aggregate: &Aggregate,
+) -> Result<String, TransError> {
+    let argument = match &aggregate.column {
+        Some(name) => {
+            let column = meta
+                .column(CRUD_R, name)
+                .ok_or_else(|| InvalidAggregate(name.clone()))?;
+            if aggregate.func != AggregateFunc::Count
+                && !NUMERIC_TYPES.contains(&&*column.column_type)
+            {
+                return Err(InvalidAggregate(name.clone()));
+            }
+            format!("{TABLE_ALIAS}.{name}")
+        }
+        None if aggregate.func == AggregateFunc::Count => "*".to_string(),
+        None => return Err(RequestMustContain("column".to_string())),
+    };
+
+    // 统一转为 DECIMAL，按同一种类型读取
+    Ok(format!(
+        "CAST({}({argument}) AS DECIMAL(65,10))",
+        aggregate.func.sql()
+    ))
+}
+
+/// 加载各列配置的字典
+async fn column_dicts(
+    pool: &DbPool,
+    columns: &[ColumnMeta],
+) -> ApiResult<HashMap<i32, Arc<Vec<DictKey>>>> {
+    let mut dicts = HashMap::new();
+    for dict_id in columns.iter().filter_map(|c| c.column_dict) {
+        if let Entry::Vacant(e) = dicts.entry(dict_id) {
+            e.insert(dict_keys(pool, dict_id).await?);
+        }
+    }
+    Ok(dicts)
+}
+
+/// 按关联查询 rows 对应的从表记录，以从表名为字段名附加到每行；从表记录超过 limit.max_children 时报错
+async fn embed_children(
+    pool: &DbPool,
+    relation: &Relation,
+    with_label: bool,
+    rows: &mut [serde_json::Map<String, Value>],
+) -> ApiResult<()> {
+    let child = match table_meta(pool, &relation.table_name, "read").await? {
+        Some(meta) if !meta.select_sql.is_empty() => meta,
+        _ => {
+            return Err(CrudInfoNotFound(relation.table_name.clone(), String::from(CRUD_R)).into())
+        }
+    };
+
+    let keys = rows
+        .iter()
+        .filter_map(|row| match row.get(&relation.parent_column) {
+            Some(Value::String(key)) if !key.is_empty() => Some(key.clone()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let dicts = if with_label {
+        column_dicts(pool, child.columns(CRUD_R)).await?
+    } else {
+        HashMap::new()
+    };
+    let select = if with_label {
+        &child.label_sql
+    } else {
+        &child.select_sql
+    };
+    // 从表的 r 列中不一定有外键，另取一列用于分组
+    let select = select.replacen(
+        "SELECT ",
+        &format!(
+            "SELECT CAST({TABLE_ALIAS}.{} AS CHAR) AS {PARENT_KEY},",
+            relation.column_name
+        ),
+        1,
+    );
+    let column_type = child.column_type(&relation.column_name).unwrap_or("TEXT");
+    let max_children = GLOBAL_CONFIG.get().unwrap().load().limit.max_children;
+
+    let mut children: HashMap<String, Vec<Value>> = HashMap::new();
+    let mut count = 0;
+    for chunk in keys.chunks(EMBED_CHUNK) {
+        let sql = format!(
+            "{select} WHERE {TABLE_ALIAS}.{} IN ({}) LIMIT {}",
+            relation.column_name,
+            vec!["?"; chunk.len()].join(","),
+            max_children - count + 1
+        );
         let mut query = sqlx::query(&sql);
-        query = query_bind_value(query, req, placeholders);
-        let res = query.execute(pool.0).await.map_err(InternalServerError)?;
+        for key in chunk {
+            query = bind_value(query, key.clone(), column_type.to_string());
+        }
+        let mut stream = query.fetch(pool);
+        while let Some(res) = stream.next().await {
+            count += 1;
+            if count > max_children {
+                return Err(TooManyFields(relation.table_name.clone(), max_children).into());
+            }
+            let mut row = row_to_json(&res?, &child, &dicts);
+            if let Some(Value::String(key)) = row.remove(PARENT_KEY) {
+                children.entry(key).or_default().push(Value::Object(row));
+            }
+        }
+    }
 
-        Ok(Json(res.rows_affected()))
+    for row in rows {
+        let items = match row.get(&relation.parent_column) {
+            Some(Value::String(key)) => children.get(key).cloned().unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        row.insert(relation.table_name.clone(), Value::Array(items));
     }
+
+    Ok(())
+}
+
+/// 在后台逐行读取导出的记录，接收端关闭后停止读取
+fn spawn_records(pool: DbPool, read: ReadQuery) -> Receiver<sqlx::Result<Vec<String>>> {
+    let (tx, rx) = mpsc::channel(EXPORT_BUFFER);
+    let task = async move {
+        let mut stream = read.query().fetch(&pool);
+        while let Some(res) = stream.next().await {
+            let record = res.map(|row| row_to_record(&row, &read.meta, &read.dicts));
+            if let Err(e) = &record {
+                tracing::error!("导出{}失败：{e}", read.meta.select_sql);
+            }
+            let failed = record.is_err();
+            if tx.send(record).await.is_err() || failed {
+                break;
+            }
+        }
+    };
+    tokio::spawn(task.in_current_span());
+    rx
+}
+
+/// 按条件列配置拼接 where 子句，校验必填条件；prefix 为查询语句中的表别名
+fn where_clause(
+    columns: &[ColumnMeta],
+    req: &HashMap<String, String>,
+    sql: &mut String,
+    placeholders: &mut Vec<(String, String)>,
+    prefix: &str,
+) -> ApiResult<()> {
+    let mut and_str = " WHERE ";
+    for column in columns {
+        if req.contains_key(&column.column_name) {
+            *sql += and_str;
+            *sql += prefix;
+            *sql += &column.column_name;
+            *sql += " = ?";
+            and_str = " AND ";
+            placeholders.push((column.column_name.clone(), column.column_type.clone()));
+        } else if column.column_must {
+            return Err(RequestMustContain(column.column_name.clone()).into());
+        }
+    }
+
+    Ok(())
+}
+
+/// 把一行查询结果转为 JSON，dicts 非空时追加字典标签
+fn row_to_json(
+    row: &MySqlRow,
+    meta: &TableMeta,
+    dicts: &HashMap<i32, Arc<Vec<DictKey>>>,
+) -> serde_json::Map<String, Value> {
+    let mut r = serde_json::Map::new();
+    row.columns().iter().enumerate().for_each(|(i, col)| {
+        // join 出的标签列已转为文本
+        let column_type = meta
+            .column(CRUD_R, col.name())
+            .map(|c| &*c.column_type)
+            .unwrap_or("TEXT");
+        r.insert(col.name().to_string(), get_value(row, i, column_type));
+    });
+
+    for column in meta.columns(CRUD_R) {
+        let keys = match column.column_dict.and_then(|d| dicts.get(&d)) {
+            Some(keys) => keys,
+            None => continue,
+        };
+        let label = match r.get(&column.column_name) {
+            Some(Value::String(key)) => keys
+                .iter()
+                .find(|k| &k.key == key)
+                .map(|k| Value::String(k.value.clone())),
+            _ => None,
+        };
+        r.insert(
+            format!("{}_label", column.column_name),
+            label.unwrap_or_default(),
+        );
+    }
+
+    r
+}
+
+/// 把一行查询结果按 r 列顺序转为导出记录，字典列和外键列使用标签
+fn row_to_record(
+    row: &MySqlRow,
+    meta: &TableMeta,
+    dicts: &HashMap<i32, Arc<Vec<DictKey>>>,
+) -> Vec<String> {
+    meta.columns(CRUD_R)
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            if meta.has_label(&column.column_name) {
+                let name = format!("{}_label", column.column_name);
+                if let Ok(Some(label)) = row.try_get::<Option<String>, _>(&*name) {
+                    return label;
+                }
+            }
+            let value = get_string(row, i, &column.column_type);
+            column
+                .column_dict
+                .and_then(|d| dicts.get(&d))
+                .and_then(|keys| keys.iter().find(|k| k.key == value))
+                .map(|k| k.value.clone())
+                .unwrap_or(value)
+        })
+        .collect()
 }
 
 fn query_bind_value<'a>(
-    mut query: Query<'a, MySql, <MySql as HasArguments>::Arguments>,
+    mut query: Query<'a, MySql, MySqlArguments>,
     mut req: HashMap<String, String>,
     columns: Vec<(String, String)>,
-) -> Query<'a, MySql, <MySql as HasArguments<'a>>::Arguments> {
+) -> Query<'a, MySql, MySqlArguments> {
     for (column_name, column_type) in columns {
         let column_value = req.remove(&*column_name);
         if let Some(column_value) = column_value {
@@ -328,7 +1532,11 @@ fn query_bind_value<'a>(
 }
 
 fn get_value(row: &MySqlRow, i: usize, value_type: &str) -> Value {
-    let value = match value_type {
+    Value::String(get_string(row, i, value_type))
+}
+
+fn get_string(row: &MySqlRow, i: usize, value_type: &str) -> String {
+    match value_type {
         "BIGINT" => row
             .try_get::<i64, usize>(i)
             .map(|v| v.to_string())
@@ -345,11 +1553,11 @@ fn get_value(row: &MySqlRow, i: usize, value_type: &str) -> Value {
             .unwrap_or_default(),
         "DATE" => row
             .try_get::<Date, usize>(i)
-            .map(|v| v.to_string())
+            .map(|v| v.format(DATE_FORMAT).unwrap_or_default())
             .unwrap_or_default(),
         "DATETIME" => row
             .try_get::<PrimitiveDateTime, usize>(i)
-            .map(|v| v.to_string())
+            .map(|v| v.format(DATETIME_FORMAT).unwrap_or_default())
             .unwrap_or_default(),
         "DECIMAL" => row
             .try_get::<BigDecimal, usize>(i)
@@ -382,7 +1590,7 @@ fn get_value(row: &MySqlRow, i: usize, value_type: &str) -> Value {
         "TEXT" => row.try_get::<String, usize>(i).unwrap_or_default(),
         "TIME" => row
             .try_get::<Time, usize>(i)
-            .map(|v| v.to_string())
+            .map(|v| v.format(TIME_FORMAT).unwrap_or_default())
             .unwrap_or_default(),
         "TINYINT" => row
             .try_get::<i8, usize>(i)
@@ -393,40 +1601,64 @@ fn get_value(row: &MySqlRow, i: usize, value_type: &str) -> Value {
             .map(|v| v.to_string())
             .unwrap_or_default(),
         _ => String::new(),
-    };
-    Value::String(value)
+    }
+}
+
+/// 时间控件在秒为 0 时会省略秒，如 `08:30`
+fn with_seconds(value: &str) -> Cow<'_, str> {
+    if value.matches(':').count() == 1 {
+        Cow::Owned(format!("{value}:00"))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// 按 `bind_value` 的规则检查值能否转为列类型
+fn check_value(value: &str, value_type: &str) -> bool {
+    match value_type {
+        "BIGINT" => value.parse::<i64>().is_ok(),
+        "BIGINT_UNSIGNED" => value.parse::<u64>().is_ok(),
+        "BOOLEAN" => value.parse::<bool>().is_ok(),
+        "DATE" => Date::parse(value, DATE_FORMAT).is_ok(),
+        "DATETIME" => PrimitiveDateTime::parse(&with_seconds(value), DATETIME_FORMAT).is_ok(),
+        "DECIMAL" => value.parse::<BigDecimal>().is_ok(),
+        "DOUBLE" => value.parse::<f64>().is_ok(),
+        "FLOAT" => value.parse::<f32>().is_ok(),
+        "INT" => value.parse::<i32>().is_ok(),
+        "INT_UNSIGNED" => value.parse::<u32>().is_ok(),
+        "SMALLINT" => value.parse::<i16>().is_ok(),
+        "SMALLINT_UNSIGNED" => value.parse::<u16>().is_ok(),
+        "TIME" => Time::parse(&with_seconds(value), TIME_FORMAT).is_ok(),
+        "TINYINT" => value.parse::<i8>().is_ok(),
+        "TINYINT_UNSIGNED" => value.parse::<u8>().is_ok(),
+        _ => true,
+    }
 }
 
 fn bind_value<'a>(
-    query: Query<'a, MySql, <MySql as HasArguments>::Arguments>,
+    query: Query<'a, MySql, MySqlArguments>,
     value: String,
     value_type: String,
-) -> Query<'a, MySql, <MySql as HasArguments<'a>>::Arguments> {
+) -> Query<'a, MySql, MySqlArguments> {
     match value_type.as_str() {
         "BIGINT" => query.bind(value.parse::<i64>().unwrap_or_default()),
         "BIGINT_UNSIGNED" => query.bind(value.parse::<u64>().unwrap_or_default()),
         "BLOB" => query.bind(value),
         "BOOLEAN" => query.bind(value.parse::<bool>().unwrap_or_default()),
-        "DATE" => {
-            let format = format_description::parse("%F").unwrap();
-            match Date::parse(&value, &format) {
-                Ok(v) => query.bind(v),
-                Err(e) => {
-                    tracing::error!("{} parse Date failed: {}", value, e);
-                    query
-                }
+        "DATE" => match Date::parse(&value, DATE_FORMAT) {
+            Ok(v) => query.bind(v),
+            Err(e) => {
+                tracing::error!("{} parse Date failed: {}", value, e);
+                query
             }
-        }
-        "DATETIME" => {
-            let format = format_description::parse("%F %T").unwrap();
-            match PrimitiveDateTime::parse(&value, &format) {
-                Ok(v) => query.bind(v),
-                Err(_) => {
-                    tracing::error!("{} parse DateTime failed", value);
-                    query
-                }
+        },
+        "DATETIME" => match PrimitiveDateTime::parse(&with_seconds(&value), DATETIME_FORMAT) {
+            Ok(v) => query.bind(v),
+            Err(_) => {
+                tracing::error!("{} parse DateTime failed", value);
+                query
             }
-        }
+        },
         "DECIMAL" => query.bind(value.parse::<BigDecimal>().unwrap_or_default()),
         "DOUBLE" => query.bind(value.parse::<f64>().unwrap_or_default()),
         "FLOAT" => query.bind(value.parse::<f32>().unwrap_or_default()),
@@ -435,18 +1667,104 @@ fn bind_value<'a>(
         "SMALLINT" => query.bind(value.parse::<i16>().unwrap_or_default()),
         "SMALLINT_UNSIGNED" => query.bind(value.parse::<u16>().unwrap_or_default()),
         "TEXT" => query.bind(value),
-        "TIME" => {
-            let format = format_description::parse("%T").unwrap();
-            match Time::parse(&value, &format) {
-                Ok(v) => query.bind(v),
-                Err(_) => {
-                    tracing::error!("{} parse Time failed", value);
-                    query
-                }
+        "TIME" => match Time::parse(&with_seconds(&value), TIME_FORMAT) {
+            Ok(v) => query.bind(v),
+            Err(_) => {
+                tracing::error!("{} parse Time failed", value);
+                query
             }
-        }
+        },
         "TINYINT" => query.bind(value.parse::<i8>().unwrap_or_default()),
         "TINYINT_UNSIGNED" => query.bind(value.parse::<u8>().unwrap_or_default()),
         _ => query,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsert_status_uses_existing_row() {
+        assert_eq!(UpsertStatus::new(false, 1), UpsertStatus::Inserted);
+        assert_eq!(UpsertStatus::new(true, 2), UpsertStatus::Updated);
+        assert_eq!(UpsertStatus::new(true, 1), UpsertStatus::Unchanged);
+        assert_eq!(UpsertStatus::new(true, 0), UpsertStatus::Unchanged);
+    }
+
+    #[test]
+    fn existing_statement_uses_complete_keys() {
+        let mut meta = TableMeta::default();
+        meta.from_sql = format!(" FROM user_info {TABLE_ALIAS}");
+        let placeholders = vec![
+            ("user_id".to_string(), "INT".to_string()),
+            ("email".to_string(), "TEXT".to_string()),
+        ];
+        let keys = vec![
+            vec!["USER_ID".to_string()],
+            vec!["email".to_string(), "tenant_id".to_string()],
+            vec!["email".to_string()],
+        ];
+        let (sql, binds) = existing_statement(&meta, &keys, &placeholders).unwrap();
+        assert_eq!(
+            sql,
+            format!(
+                "SELECT 1 FROM user_info {TABLE_ALIAS} WHERE (user_id = ?) OR (email = ?) LIMIT 1 FOR UPDATE"
+            )
+        );
+        assert_eq!(binds, placeholders);
+
+        let keys = vec![vec!["tenant_id".to_string()]];
+        assert!(existing_statement(&meta, &keys, &placeholders).is_none());
+    }
+
+    #[test]
+    fn aggregate_expr_allows_only_r_columns() {
+        let meta = cache::test_table(
+            "order_info",
+            &[
+                ("r", "amount", "DECIMAL"),
+                ("r", "remark", "TEXT"),
+                ("s", "price", "INT"),
+            ],
+        );
+        let aggregate = |func, column: Option<&str>| Aggregate {
+            func,
+            column: column.map(str::to_string),
+        };
+
+        assert_eq!(
+            aggregate_expr(&meta, &aggregate(AggregateFunc::Sum, Some("amount"))).unwrap(),
+            format!("CAST(SUM({TABLE_ALIAS}.amount) AS DECIMAL(65,10))")
+        );
+        assert_eq!(
+            aggregate_expr(&meta, &aggregate(AggregateFunc::Count, None)).unwrap(),
+            "CAST(COUNT(*) AS DECIMAL(65,10))"
+        );
+        // 计数可以用于非数值列，其余聚合只能用于数值列
+        assert!(aggregate_expr(&meta, &aggregate(AggregateFunc::Count, Some("remark"))).is_ok());
+        assert!(aggregate_expr(&meta, &aggregate(AggregateFunc::Avg, Some("remark"))).is_err());
+        // 不是 r 列或不存在的列
+        assert!(aggregate_expr(&meta, &aggregate(AggregateFunc::Max, Some("price"))).is_err());
+        assert!(aggregate_expr(&meta, &aggregate(AggregateFunc::Min, Some("1);drop"))).is_err());
+        assert!(aggregate_expr(&meta, &aggregate(AggregateFunc::Sum, None)).is_err());
+    }
+
+    #[test]
+    fn group_columns_allows_only_r_columns() {
+        let meta = cache::test_table(
+            "order_info",
+            &[
+                ("r", "status", "VARCHAR"),
+                ("r", "file", "BLOB"),
+                ("s", "price", "INT"),
+            ],
+        );
+        let group_by = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        let columns = group_columns(&meta, &group_by(&["status"])).unwrap();
+        assert_eq!(columns[0].column_name, "status");
+        assert!(group_columns(&meta, &group_by(&["file"])).is_err());
+        assert!(group_columns(&meta, &group_by(&["status", "price"])).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use poem::web::Data;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::{Enum, Object, OpenApi};
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+
+use crate::error::ApiResult;
+use crate::error::TransError::{CrudInfoNotFound, RequestMustContain, WidgetNotFound};
+use crate::web::api::crud::{aggregate_expr, group_columns, Aggregate};
+use crate::web::api::ApiTags;
+use crate::web::meta::cache;
+use crate::web::user::{self, owner_clause, OWNER_ROLE};
+use crate::web::DbPool;
+
+pub(crate) struct DashboardApi;
+
+const SQL_WIDGETS: &str = "SELECT `widget_id`,`owner_type`,`owner_id`,`widget_type`,`title`,`table_name`,`query`,`widget_order` FROM dashboard_widget WHERE ";
+const SQL_WIDGET_CREATE: &str = "INSERT INTO dashboard_widget (`owner_type`,`owner_id`,`widget_type`,`title`,`table_name`,`query`,`widget_order`) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const SQL_WIDGET_UPDATE: &str = "UPDATE dashboard_widget SET `owner_type` = ?,`owner_id` = ?,`widget_type` = ?,`title` = ?,`table_name` = ?,`query` = ?,`widget_order` = ? WHERE `widget_id` = ? AND ";
+const SQL_WIDGET_DELETE: &str = "DELETE FROM dashboard_widget WHERE `widget_id` = ? AND ";
+
+/// (编号, 所属类型, 用户或角色, 组件类型, 标题, 表名, 统计条件, 顺序)
+type WidgetRow = (u32, String, String, String, String, String, String, i32);
+
+#[derive(Enum, Clone, Copy)]
+#[oai(rename_all = "lowercase")]
+enum WidgetType {
+    Kpi,
+    Bar,
+    Line,
+    Pie,
+}
+
+impl WidgetType {
+    fn code(self) -> &'static str {
+        match self {
+            WidgetType::Kpi => "kpi",
+            WidgetType::Bar => "bar",
+            WidgetType::Line => "line",
+            WidgetType::Pie => "pie",
+        }
+    }
+
+    fn from_code(code: &str) -> Self {
+        match code {
+            "bar" => WidgetType::Bar,
+            "line" => WidgetType::Line,
+            "pie" => WidgetType::Pie,
+            _ => WidgetType::Kpi,
+        }
+    }
+}
+
+/// 组件的统计条件，与 `/crud/aggregate` 的请求对应
+#[derive(Object, Serialize, Deserialize, Default)]
+struct WidgetQuery {
+    /// 图表的分类列，kpi 为空
+    group_by: Vec<String>,
+    aggregates: Vec<Aggregate>,
+    conditions: HashMap<String, String>,
+}
+
+#[derive(Object)]
+struct Widget {
+    /// 新增时为空
+    widget_id: Option<u32>,
+    /// 保存到该角色，为空时保存到当前用户
+    role: Option<String>,
+    widget_type: WidgetType,
+    title: String,
+    table_name: String,
+    query: WidgetQuery,
+    widget_order: i32,
+}
+
+/// 当前用户可见的组件
+#[derive(Object)]
+struct Dashboard {
+    /// 当前用户，匿名时为空，只能查看不能保存组件
+    user_id: Option<String>,
+    /// 当前用户的角色，组件可以保存到其中之一
+    roles: Vec<String>,
+    widgets: Vec<Widget>,
+}
+
+#[OpenApi(prefix_path = "/dashboard", tag = "ApiTags::Dashboard")]
+impl DashboardApi {
+    /// 当前用户及其角色的组件，按 widget_order 排序
+    #[oai(path = "/widgets", method = "get", operation_id = "dashboard_widgets")]
+    async fn widgets(&self, pool: Data<&DbPool>) -> ApiResult<Json<Dashboard>> {
+        let user = user::current();
+        let owners = user.owners();
+        let sql = format!(
+            "{SQL_WIDGETS}{} ORDER BY `widget_order`,`widget_id`",
+            owner_clause(&owners)
+        );
+        let mut query = sqlx::query_as::<_, WidgetRow>(&sql);
+        for (owner_type, owner_id) in owners {
+            query = query.bind(owner_type).bind(owner_id);
+        }
+
+        let mut widgets = Vec::new();
+        let mut stream = query.fetch(pool.0);
+        while let Some(res) = stream.next().await {
+            let (widget_id, owner_type, owner_id, widget_type, title, table_name, query, order) =
+                res?;
+            // 无法解析的统计条件按空条件返回，由用户重新编辑
+            let query = serde_json::from_str(&query).unwrap_or_else(|e| {
+                tracing::warn!("仪表盘组件{widget_id}的统计条件无法解析：{e}");
+                WidgetQuery::default()
+            });
+            widgets.push(Widget {
+                widget_id: Some(widget_id),
+                role: (owner_type == OWNER_ROLE).then_some(owner_id),
+                widget_type: WidgetType::from_code(&widget_type),
+                title,
+                table_name,
+                query,
+                widget_order: order,
+            });
+        }
+
+        Ok(Json(Dashboard {
+            user_id: user.id.clone(),
+            roles: user.roles.clone(),
+            widgets,
+        }))
+    }
+
+    /// 新增或修改组件，返回组件编号；只能修改本人或所属角色的组件
+    #[oai(
+        path = "/widgets",
+        method = "post",
+        operation_id = "dashboard_save_widget"
+    )]
+    async fn save(&self, pool: Data<&DbPool>, widget: Json<Widget>) -> ApiResult<Json<u32>> {
+        let widget = widget.0;
+        let user = user::current();
+        let (owner_type, owner_id) = user.owner(widget.role.as_deref())?;
+        if widget.query.aggregates.is_empty() {
+            return Err(RequestMustContain("aggregates".to_string()).into());
+        }
+        let meta = match cache::table(pool.0, &widget.table_name).await? {
+            Some(meta) if !meta.select_sql.is_empty() => meta,
+            _ => return Err(CrudInfoNotFound(widget.table_name.clone(), "r".to_string()).into()),
+        };
+        // 与统计时相同的校验，避免保存无法使用的分组和聚合
+        group_columns(&meta, &widget.query.group_by)?;
+        for aggregate in &widget.query.aggregates {
+            aggregate_expr(&meta, aggregate)?;
+        }
+
+        let query = serde_json::to_string(&widget.query).unwrap_or_default();
+        let widget_id = match widget.widget_id {
+            Some(widget_id) => widget_id,
+            None => {
+                let res = sqlx::query(SQL_WIDGET_CREATE)
+                    .bind(owner_type)
+                    .bind(owner_id)
+                    .bind(widget.widget_type.code())
+                    .bind(widget.title)
+                    .bind(widget.table_name)
+                    .bind(query)
+                    .bind(widget.widget_order)
+                    .execute(pool.0)
+                    .await?;
+                return Ok(Json(res.last_insert_id() as u32));
+            }
+        };
+
+        let owners = user.owners();
+        let sql = format!("{SQL_WIDGET_UPDATE}{}", owner_clause(&owners));
+        let mut update = sqlx::query(&sql)
+            .bind(owner_type)
+            .bind(owner_id)
+            .bind(widget.widget_type.code())
+            .bind(widget.title)
+            .bind(widget.table_name)
+            .bind(query)
+            .bind(widget.widget_order)
+            .bind(widget_id);
+        for (owner_type, owner_id) in owners {
+            update = update.bind(owner_type).bind(owner_id);
+        }
+        if update.execute(pool.0).await?.rows_affected() == 0 {
+            return Err(WidgetNotFound(widget_id).into());
+        }
+
+        Ok(Json(widget_id))
+    }
+
+    #[oai(
+        path = "/widgets/:widget_id",
+        method = "delete",
+        operation_id = "dashboard_delete_widget"
+    )]
+    async fn delete(&self, pool: Data<&DbPool>, widget_id: Path<u32>) -> ApiResult<Json<u64>> {
+        let owners = user::current().owners();
+        let sql = format!("{SQL_WIDGET_DELETE}{}", owner_clause(&owners));
+        let mut delete = sqlx::query(&sql).bind(widget_id.0);
+        for (owner_type, owner_id) in owners {
+            delete = delete.bind(owner_type).bind(owner_id);
+        }
+        let res = delete.execute(pool.0).await?;
+        if res.rows_affected() == 0 {
+            return Err(WidgetNotFound(widget_id.0).into());
+        }
+
+        Ok(Json(res.rows_affected()))
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use arc_swap::ArcSwap;
+use poem::web::Data;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::{Object, OpenApi};
+use tokio_stream::StreamExt;
+
+use crate::error::ApiResult;
+use crate::error::TransError::DictNotFound;
+use crate::web::api::ApiTags;
+use crate::web::DbPool;
+
+pub(crate) struct DictApi;
+
+const DICT_TABLE: &str = "dict_info";
+
+type DictCache = HashMap<i32, Arc<Vec<DictKey>>>;
+
+/// 字典缓存，按 DICT_ID 懒加载，写操作后失效
+static DICT_CACHE: OnceLock<ArcSwap<DictCache>> = OnceLock::new();
+/// 每次失效加一，加载期间发生过失效时不写入缓存，避免写回旧数据
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+const READ_DICT_LIST: &str = "select `dict_id`, `dict_name` from dict_info group by `dict_id`, `dict_name` order by `dict_id`";
+const READ_DICT_KEYS: &str = "select `key_order`, `key`, `value`, `desc` from dict_info where `dict_id` = ? order by `key_order`";
+const DICT_KEY_CREATE: &str = "insert into dict_info (`dict_id`, `dict_name`, `key_order`, `key`, `value`, `desc`) values (?, ?, ?, ?, ?, ?)";
+const DICT_RENAME: &str = "update dict_info set `dict_name` = ? where `dict_id` = ?";
+const DICT_DELETE: &str = "delete from dict_info where `dict_id` = ?";
+const DICT_KEY_UPDATE: &str = "update dict_info set `key_order` = ?, `value` = ?, `desc` = ? where `dict_id` = ? and `key` = ?";
+const DICT_KEY_DELETE: &str = "delete from dict_info where `dict_id` = ? and `key` = ?";
+const READ_DICT_NAME: &str = "select `dict_name` from dict_info where `dict_id` = ? limit 1";
+
+#[derive(Object, sqlx::FromRow)]
+struct Dict {
+    dict_id: i32,
+    dict_name: String,
+}
+
+#[derive(Object)]
+struct DictInfo {
+    dict_id: i32,
+    dict_name: String,
+    keys: Vec<DictKey>,
+}
+
+#[derive(Object, sqlx::FromRow, Clone)]
+pub(crate) struct DictKey {
+    pub(crate) key_order: i32,
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) desc: Option<String>,
+}
+
+#[OpenApi(prefix_path = "/dict", tag = "ApiTags::Dict")]
+impl DictApi {
+    #[oai(path = "/", method = "get", operation_id = "dict_read_all")]
+    async fn read_all(&self, pool: Data<&DbPool>) -> ApiResult<Json<Vec<Dict>>> {
+        let mut dicts = Vec::new();
+        let mut stream = sqlx::query_as::<_, Dict>(READ_DICT_LIST).fetch(pool.0);
+        while let Some(res) = stream.next().await {
+            dicts.push(res?);
+        }
+
+        Ok(Json(dicts))
+    }
+
+    #[oai(path = "/", method = "post", operation_id = "dict_create")]
+    async fn create(&self, pool: Data<&DbPool>, dict: Json<DictInfo>) -> ApiResult<Json<u64>> {
+        let dict = dict.0;
+        let mut tx = pool.0.begin().await?;
+        let mut count = 0;
+        for key in dict.keys {
+            count += sqlx::query(DICT_KEY_CREATE)
+                .bind(dict.dict_id)
+                .bind(&dict.dict_name)
+                .bind(key.key_order)
+                .bind(key.key)
+                .bind(key.value)
+                .bind(key.desc)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+
+        invalidate(dict.dict_id);
+        Ok(Json(count))
+    }
+
+    #[oai(path = "/", method = "put", operation_id = "dict_rename")]
+    async fn rename(&self, pool: Data<&DbPool>, dict: Json<Dict>) -> ApiResult<Json<u64>> {
+        let count = sqlx::query(DICT_RENAME)
+            .bind(&dict.dict_name)
+            .bind(dict.dict_id)
+            .execute(pool.0)
+            .await?
+            .rows_affected();
+
+        invalidate(dict.dict_id);
+        Ok(Json(count))
+    }
+
+    #[oai(path = "/:dict_id", method = "delete", operation_id = "dict_delete")]
+    async fn delete(&self, pool: Data<&DbPool>, dict_id: Path<i32>) -> ApiResult<Json<u64>> {
+        let count = sqlx::query(DICT_DELETE)
+            .bind(dict_id.0)
+            .execute(pool.0)
+            .await?
+            .rows_affected();
+
+        invalidate(dict_id.0);
+        Ok(Json(count))
+    }
+
+    #[oai(path = "/:dict_id", method = "get", operation_id = "dict_read_keys")]
+    async fn read_keys(
+        &self,
+        pool: Data<&DbPool>,
+        dict_id: Path<i32>,
+    ) -> ApiResult<Json<Vec<DictKey>>> {
+        let keys = dict_keys(pool.0, dict_id.0).await?;
+        Ok(Json(keys.to_vec()))
+    }
+
+    #[oai(
+        path = "/:dict_id/key",
+        method = "post",
+        operation_id = "dict_create_key"
+    )]
+    async fn create_key(
+        &self,
+        pool: Data<&DbPool>,
+        dict_id: Path<i32>,
+        key: Json<DictKey>,
+    ) -> ApiResult<Json<u64>> {
+        let key = key.0;
+        let dict_name = sqlx::query_scalar::<_, String>(READ_DICT_NAME)
+            .bind(dict_id.0)
+            .fetch_optional(pool.0)
+            .await?
+            .ok_or(DictNotFound(dict_id.0))?;
+
+        let count = sqlx::query(DICT_KEY_CREATE)
+            .bind(dict_id.0)
+            .bind(dict_name)
+            .bind(key.key_order)
+            .bind(key.key)
+            .bind(key.value)
+            .bind(key.desc)
+            .execute(pool.0)
+            .await?
+            .rows_affected();
+
+        invalidate(dict_id.0);
+        Ok(Json(count))
+    }
+
+    #[oai(
+        path = "/:dict_id/key",
+        method = "put",
+        operation_id = "dict_update_key"
+    )]
+    async fn update_key(
+        &self,
+        pool: Data<&DbPool>,
+        dict_id: Path<i32>,
+        key: Json<DictKey>,
+    ) -> ApiResult<Json<u64>> {
+        let key = key.0;
+        let count = sqlx::query(DICT_KEY_UPDATE)
+            .bind(key.key_order)
+            .bind(key.value)
+            .bind(key.desc)
+            .bind(dict_id.0)
+            .bind(key.key)
+            .execute(pool.0)
+            .await?
+            .rows_affected();
+
+        invalidate(dict_id.0);
+        Ok(Json(count))
+    }
+
+    #[oai(
+        path = "/:dict_id/key/:key",
+        method = "delete",
+        operation_id = "dict_delete_key"
+    )]
+    async fn delete_key(
+        &self,
+        pool: Data<&DbPool>,
+        dict_id: Path<i32>,
+        key: Path<String>,
+    ) -> ApiResult<Json<u64>> {
+        let count = sqlx::query(DICT_KEY_DELETE)
+            .bind(dict_id.0)
+            .bind(&key.0)
+            .execute(pool.0)
+            .await?
+            .rows_affected();
+
+        invalidate(dict_id.0);
+        Ok(Json(count))
+    }
+}
+
+fn cache() -> &'static ArcSwap<DictCache> {
+    DICT_CACHE.get_or_init(|| ArcSwap::from_pointee(HashMap::new()))
+}
+
+/// 读取字典的全部 KEY（按 KEY_ORDER 排序），优先走缓存
+pub(crate) async fn dict_keys(pool: &DbPool, dict_id: i32) -> sqlx::Result<Arc<Vec<DictKey>>> {
+    if let Some(keys) = cache().load().get(&dict_id) {
+        return Ok(Arc::clone(keys));
+    }
+
+    let generation = GENERATION.load(Ordering::Acquire);
+    let mut keys = Vec::new();
+    let mut stream = sqlx::query_as::<_, DictKey>(READ_DICT_KEYS)
+        .bind(dict_id)
+        .fetch(pool);
+    while let Some(res) = stream.next().await {
+        keys.push(res?);
+    }
+
+    let keys = Arc::new(keys);
+    // invalidate 总会替换缓存，与其并发时 rcu 会重试并在这里看到新的 generation
+    cache().rcu(|c| {
+        let mut c = HashMap::clone(c);
+        if GENERATION.load(Ordering::Acquire) == generation {
+            c.insert(dict_id, Arc::clone(&keys));
+        }
+        c
+    });
+    Ok(keys)
+}
+
+/// 通过通用 CRUD 接口写入 dict_info 时不知道改了哪个字典，使全部字典缓存失效
+pub(crate) fn invalidate_if_dict(table_name: &str) {
+    if table_name.eq_ignore_ascii_case(DICT_TABLE) {
+        GENERATION.fetch_add(1, Ordering::AcqRel);
+        cache().store(Arc::new(HashMap::new()));
+    }
+}
+
+/// 字典有变更时使缓存失效，下次读取重新加载
+fn invalidate(dict_id: i32) {
+    GENERATION.fetch_add(1, Ordering::AcqRel);
+    cache().rcu(|c| {
+        let mut c = HashMap::clone(c);
+        c.remove(&dict_id);
+        c
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalidate_if_dict_clears_only_for_dict_table() {
+        cache().store(Arc::new(HashMap::from([(1, Arc::new(Vec::new()))])));
+        invalidate_if_dict("user_info");
+        assert!(cache().load().contains_key(&1));
+
+        let generation = GENERATION.load(Ordering::Acquire);
+        invalidate_if_dict("DICT_INFO");
+        assert!(cache().load().is_empty());
+        assert!(GENERATION.load(Ordering::Acquire) > generation);
+    }
+}
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, Seek};
+use std::sync::{Arc, Mutex};
+
+use poem::Body;
+use rust_xlsxwriter::Workbook;
+use tokio::sync::mpsc::Receiver;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+
+use crate::error::TransError::{self, Export};
+use crate::error::{ApiError, ApiResult};
+
+/// Excel 打开 UTF-8 编码的 CSV 时需要 BOM 才能正确显示中文
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// 导出中途出错时追加的最后一行，避免被截断的文件看起来是完整的
+const ERROR_MARKER: &str = "#EXPORT_FAILED";
+
+/// 以这些字符开头的单元格会被表格软件当作公式执行
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+type Records = Receiver<sqlx::Result<Vec<String>>>;
+
+/// 边读边写的 CSV，读取出错时先写入错误标记行再中断响应
+pub(crate) fn csv(headers: Vec<String>, records: Records) -> Body {
+    let header = encode(&headers).map(|line| [UTF8_BOM, &line].concat());
+    let failed = Arc::new(Mutex::new(None));
+    let rows = ReceiverStream::new(records).map({
+        let failed = failed.clone();
+        move |record| match record {
+            Ok(record) => encode(&record),
+            Err(e) => {
+                tracing::error!("导出中断：{e}");
+                *failed.lock().unwrap_or_else(|e| e.into_inner()) = Some(io::Error::other(e));
+                encode(&[ERROR_MARKER.to_string()])
+            }
+        }
+    });
+    // 在标记行之后返回错误，连接被中断，客户端不会把文件当作下载成功
+    let abort = tokio_stream::iter(std::iter::from_fn(move || {
+        failed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .map(Err)
+    }));
+    Body::from_bytes_stream(tokio_stream::once(header).chain(rows).chain(abort))
+}
+
+fn encode(record: &[String]) -> io::Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(record.iter().map(|cell| escape_formula(cell)))?;
+    writer.into_inner().map_err(|e| e.into_error())
+}
+
+/// 可能被当作公式的单元格前加 `'`，防止 CSV 注入；`-1`、`+1.5` 等数字原样导出
+fn escape_formula(cell: &str) -> Cow<'_, [u8]> {
+    if cell.starts_with(FORMULA_PREFIXES) && cell.parse::<f64>().is_err() {
+        Cow::Owned(format!("'{cell}").into_bytes())
+    } else {
+        Cow::Borrowed(cell.as_bytes())
+    }
+}
+
+/// 以常量内存模式写入临时文件，写完后从文件读取响应
+pub(crate) async fn xlsx(headers: Vec<String>, records: Records) -> ApiResult<Body> {
+    let file = tokio::task::spawn_blocking(move || write_xlsx(headers, records))
+        .await
+        .map_err(export_failed)??;
+    Ok(Body::from_async_read(tokio::fs::File::from_std(file)))
+}
+
+fn write_xlsx(headers: Vec<String>, mut records: Records) -> Result<File, ApiError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet_with_constant_memory();
+    sheet.write_row(0, 0, headers).map_err(export_failed)?;
+    let mut row = 1;
+    while let Some(record) = records.blocking_recv() {
+        sheet.write_row(row, 0, record?).map_err(export_failed)?;
+        row += 1;
+    }
+
+    let mut file = tempfile::tempfile().map_err(export_failed)?;
+    workbook.save_to_writer(&mut file).map_err(export_failed)?;
+    file.rewind().map_err(export_failed)?;
+    Ok(file)
+}
+
+fn export_failed(e: impl Display) -> TransError {
+    Export(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_formula_prefixes_formula_cells() {
+        for cell in ["=1+1", "+1+1", "-1+A1", "-A1", "@SUM(A1)", "\tx", "\rx"] {
+            assert_eq!(*escape_formula(cell), *format!("'{cell}").as_bytes());
+        }
+        for cell in ["-1", "+1.5", "-0.25", "a=1"] {
+            assert_eq!(*escape_formula(cell), *cell.as_bytes());
+        }
+        assert_eq!(*escape_formula(""), *b"");
+    }
+
+    #[test]
+    fn encode_escapes_cells() {
+        let line = encode(&["=cmd".to_string(), "ok".to_string()]).unwrap();
+        assert_eq!(line, b"'=cmd,ok\n");
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use poem_openapi::types::multipart::Upload;
+use poem_openapi::Object;
+
+use crate::error::ErrorBody;
+use crate::error::TransError::{self, ImportJobNotFound, InvalidImportFile, TooManyFields};
+use crate::web::api::crud::UpsertStatus;
+use crate::web::i18n;
+
+/// 导入任务结束后保留进度的时间
+const JOB_TTL: Duration = Duration::from_secs(600);
+/// 校验报告最多返回的行错误数
+const MAX_REPORT_ERRORS: usize = 1000;
+/// 按电子表格解析的扩展名，其余按 CSV 解析
+const SPREADSHEET_EXTENSIONS: [&str; 5] = ["xlsx", "xlsm", "xlsb", "xls", "ods"];
+
+static JOBS: OnceLock<Mutex<HashMap<String, Arc<ImportJob>>>> = OnceLock::new();
+
+/// 上传文件的表头及各行单元格
+#[derive(Default)]
+pub(crate) struct Sheet {
+    pub(crate) headers: Vec<String>,
+    /// (文件中的行号, 单元格)
+    pub(crate) rows: Vec<(u64, Vec<String>)>,
+}
+
+/// 解析上传的 CSV 或电子表格的第一个工作表，首行为表头
+pub(crate) async fn read(upload: Upload, max_rows: usize) -> Result<Sheet, TransError> {
+    let spreadsheet = upload
+        .file_name()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, ext)| {
+            SPREADSHEET_EXTENSIONS
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        });
+    let file = upload.into_file().into_std().await;
+    tokio::task::spawn_blocking(move || {
+        if spreadsheet {
+            read_spreadsheet(file, max_rows)
+        } else {
+            read_csv(file, max_rows)
+        }
+    })
+    .await
+    .map_err(|e| InvalidImportFile(e.to_string()))?
+}
+
+fn read_csv(file: File, max_rows: usize) -> Result<Sheet, TransError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(BufReader::new(file));
+    let headers = reader
+        .headers()
+        .map_err(|e| InvalidImportFile(e.to_string()))?
+        .iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        if rows.len() == max_rows {
+            return Err(TooManyFields("rows".to_string(), max_rows));
+        }
+        let record = record.map_err(|e| InvalidImportFile(e.to_string()))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default();
+        rows.push((line, record.iter().map(|v| v.trim().to_string()).collect()));
+    }
+
+    Ok(Sheet { headers, rows })
+}
+
+fn read_spreadsheet(mut file: File, max_rows: usize) -> Result<Sheet, TransError> {
+    // calamine 需要可克隆的读取器，上传大小已由 max_upload_size 限制
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .map_err(|e| InvalidImportFile(e.to_string()))?;
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(data))
+        .map_err(|e| InvalidImportFile(e.to_string()))?;
+    let range = match workbook.worksheet_range_at(0) {
+        Some(range) => range.map_err(|e| InvalidImportFile(e.to_string()))?,
+        None => return Ok(Sheet::default()),
+    };
+    if range.height() > max_rows + 1 {
+        return Err(TooManyFields("rows".to_string(), max_rows));
+    }
+
+    // 区域可能不从第一行开始，行号按文件中的位置计算
+    let first_line = range
+        .start()
+        .map(|(row, _)| u64::from(row) + 1)
+        .unwrap_or(1);
+    let mut rows = range
+        .rows()
+        .map(|row| row.iter().map(cell_text).collect::<Vec<_>>());
+    let headers = rows.next().unwrap_or_default();
+    let rows = (first_line + 1..).zip(rows).collect();
+
+    Ok(Sheet { headers, rows })
+}
+
+/// 日期时间单元格转为与 `bind_value` 一致的文本
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::DateTime(dt) if dt.is_datetime() => {
+            let (y, mon, d, h, min, s, _) = dt.to_ymd_hms_milli();
+            if dt.as_f64() < 1.0 {
+                format!("{h:02}:{min:02}:{s:02}")
+            } else if (h, min, s) == (0, 0, 0) {
+                format!("{y:04}-{mon:02}-{d:02}")
+            } else {
+                format!("{y:04}-{mon:02}-{d:02} {h:02}:{min:02}:{s:02}")
+            }
+        }
+        Data::DateTimeIso(v) => v.replacen('T', " ", 1),
+        cell => cell.to_string().trim().to_string(),
+    }
+}
+
+pub(crate) enum RowErrorKind {
+    Required,
+    /// 无法按列类型解析
+    InvalidValue(String),
+    /// 不是字典中的 KEY 或标签
+    NotInDict,
+}
+
+/// 单元格的校验错误
+#[derive(Object)]
+pub(crate) struct RowError {
+    /// 文件中的行号，表头为第 1 行
+    row: u64,
+    column: String,
+    /// REQUIRED、INVALID_VALUE 或 NOT_IN_DICT
+    code: String,
+    message: String,
+}
+
+/// 导入的校验结果，写入时附带任务 ID
+#[derive(Object, Default)]
+pub(crate) struct ImportReport {
+    /// 非空行数
+    pub(crate) total: u64,
+    pub(crate) valid: u64,
+    pub(crate) invalid: u64,
+    /// 没有对应 c 列的表头
+    pub(crate) unmapped: Vec<String>,
+    /// 最多返回前 1000 个错误
+    errors: Vec<RowError>,
+    pub(crate) job_id: Option<String>,
+}
+
+impl ImportReport {
+    pub(crate) fn error(&mut self, row: u64, column: &str, label: &str, kind: RowErrorKind) {
+        if self.errors.len() >= MAX_REPORT_ERRORS {
+            return;
+        }
+        let (code, message) = match kind {
+            RowErrorKind::Required => (
+                "REQUIRED",
+                i18n::tr("import.required", &[("label", &label)]),
+            ),
+            RowErrorKind::InvalidValue(t) => (
+                "INVALID_VALUE",
+                i18n::tr("import.invalid_value", &[("label", &label), ("type", &t)]),
+            ),
+            RowErrorKind::NotInDict => (
+                "NOT_IN_DICT",
+                i18n::tr("import.not_in_dict", &[("label", &label)]),
+            ),
+        };
+        self.errors.push(RowError {
+            row,
+            column: column.to_string(),
+            code: code.to_string(),
+            message,
+        });
+    }
+}
+
+/// 导入任务的进度
+#[derive(Object)]
+pub(crate) struct ImportProgress {
+    job_id: String,
+    total: u64,
+    processed: u64,
+    inserted: u64,
+    updated: u64,
+    unchanged: u64,
+    done: bool,
+    /// 失败时整个事务已回滚
+    error: Option<ErrorBody>,
+}
+
+#[derive(Default)]
+struct Progress {
+    processed: u64,
+    inserted: u64,
+    updated: u64,
+    unchanged: u64,
+    finished_at: Option<Instant>,
+    error: Option<TransError>,
+}
+
+/// 后台执行的导入任务
+pub(crate) struct ImportJob {
+    id: String,
+    total: u64,
+    progress: Mutex<Progress>,
+}
+
+impl ImportJob {
+    /// 登记新任务，同时清理结束较久的任务
+    pub(crate) fn start(total: u64) -> Arc<Self> {
+        let job = Arc::new(ImportJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            total,
+            progress: Mutex::new(Progress::default()),
+        });
+        let mut jobs = jobs().lock().unwrap_or_else(|e| e.into_inner());
+        jobs.retain(|_, j| j.lock().finished_at.is_none_or(|t| t.elapsed() < JOB_TTL));
+        jobs.insert(job.id.clone(), Arc::clone(&job));
+        job
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub(crate) fn row_done(&self, status: UpsertStatus) {
+        let mut progress = self.lock();
+        progress.processed += 1;
+        match status {
+            UpsertStatus::Inserted => progress.inserted += 1,
+            UpsertStatus::Updated => progress.updated += 1,
+            UpsertStatus::Unchanged => progress.unchanged += 1,
+        }
+    }
+
+    pub(crate) fn finish(&self, error: Option<TransError>) {
+        let mut progress = self.lock();
+        progress.finished_at = Some(Instant::now());
+        progress.error = error;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Progress> {
+        self.progress.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn jobs() -> &'static Mutex<HashMap<String, Arc<ImportJob>>> {
+    JOBS.get_or_init(Default::default)
+}
+
+pub(crate) fn progress(job_id: &str) -> Result<ImportProgress, TransError> {
+    let job = jobs()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(job_id)
+        .cloned()
+        .ok_or_else(|| ImportJobNotFound(job_id.to_string()))?;
+    let progress = job.lock();
+    Ok(ImportProgress {
+        job_id: job.id.clone(),
+        total: job.total,
+        processed: progress.processed,
+        inserted: progress.inserted,
+        updated: progress.updated,
+        unchanged: progress.unchanged,
+        done: progress.finished_at.is_some(),
+        error: progress.error.as_ref().map(TransError::body),
+    })
+}
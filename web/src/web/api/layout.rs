@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use poem::web::Data;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::{Object, OpenApi};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ApiResult;
+use crate::error::TransError::TooManyFields;
+use crate::web::api::ApiTags;
+use crate::web::user;
+use crate::web::DbPool;
+use crate::GLOBAL_CONFIG;
+
+pub(crate) struct LayoutApi;
+
+const SQL_LAYOUT: &str =
+    "SELECT `layout` FROM grid_layout WHERE `owner_id` = ? AND `table_name` = ?";
+const SQL_LAYOUT_SAVE: &str = "INSERT INTO grid_layout (`owner_id`,`table_name`,`layout`) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE `layout` = VALUES(`layout`)";
+const SQL_LAYOUT_DELETE: &str = "DELETE FROM grid_layout WHERE `owner_id` = ? AND `table_name` = ?";
+
+/// 用户在一张表上的列布局
+#[derive(Object, Serialize, Deserialize, Default)]
+struct GridLayout {
+    /// 列的顺序，未列出的列按配置顺序排在后面
+    columns: Vec<String>,
+    /// 隐藏的列
+    hidden: Vec<String>,
+    /// 列宽，单位为像素
+    widths: HashMap<String, u32>,
+    /// 固定在左侧的列数
+    frozen: u32,
+}
+
+impl GridLayout {
+    fn check_limits(&self) -> ApiResult<()> {
+        let max_columns = GLOBAL_CONFIG.get().unwrap().load().limit.max_columns;
+        let fields = [
+            ("columns", self.columns.len()),
+            ("hidden", self.hidden.len()),
+            ("widths", self.widths.len()),
+        ];
+        match fields.iter().find(|(_, len)| *len > max_columns) {
+            Some((name, _)) => Err(TooManyFields(name.to_string(), max_columns).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[OpenApi(prefix_path = "/layout", tag = "ApiTags::Layout")]
+impl LayoutApi {
+    /// 当前用户的列布局，没有保存过时为空布局；没有用户标识时返回 401
+    #[oai(path = "/:table_name", method = "get", operation_id = "layout_read")]
+    async fn read(
+        &self,
+        pool: Data<&DbPool>,
+        table_name: Path<String>,
+    ) -> ApiResult<Json<GridLayout>> {
+        let user = user::current();
+        let owner_id = user.require_id()?;
+        let layout = sqlx::query_scalar::<_, String>(SQL_LAYOUT)
+            .bind(owner_id)
+            .bind(&table_name.0)
+            .fetch_optional(pool.0)
+            .await?;
+
+        // 无法解析的布局按空布局返回，下次保存时覆盖
+        let layout = layout
+            .map(|layout| {
+                serde_json::from_str(&layout).unwrap_or_else(|e| {
+                    tracing::warn!("{owner_id}在{}上的布局无法解析：{e}", table_name.0);
+                    GridLayout::default()
+                })
+            })
+            .unwrap_or_default();
+        Ok(Json(layout))
+    }
+
+    /// 保存当前用户的列布局
+    #[oai(path = "/:table_name", method = "put", operation_id = "layout_save")]
+    async fn save(
+        &self,
+        pool: Data<&DbPool>,
+        table_name: Path<String>,
+        layout: Json<GridLayout>,
+    ) -> ApiResult<Json<u64>> {
+        layout.check_limits()?;
+        let user = user::current();
+        let layout = serde_json::to_string(&layout.0).unwrap_or_default();
+        let res = sqlx::query(SQL_LAYOUT_SAVE)
+            .bind(user.require_id()?)
+            .bind(table_name.0)
+            .bind(layout)
+            .execute(pool.0)
+            .await?;
+        Ok(Json(res.rows_affected()))
+    }
+
+    /// 恢复为默认布局
+    #[oai(
+        path = "/:table_name",
+        method = "delete",
+        operation_id = "layout_reset"
+    )]
+    async fn reset(&self, pool: Data<&DbPool>, table_name: Path<String>) -> ApiResult<Json<u64>> {
+        let user = user::current();
+        let res = sqlx::query(SQL_LAYOUT_DELETE)
+            .bind(user.require_id()?)
+            .bind(table_name.0)
+            .execute(pool.0)
+            .await?;
+        Ok(Json(res.rows_affected()))
+    }
+}
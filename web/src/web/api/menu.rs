@@ -1,11 +1,11 @@
+use crate::error::ApiResult;
+use crate::error::TransError::MenuNotFound;
 use crate::web::api::ApiTags;
 use crate::web::DbPool;
-use poem::error::InternalServerError;
 use poem::web::Data;
-use poem::Result;
-use poem_openapi::param::Path;
-use poem_openapi::payload::{Json, PlainText};
-use poem_openapi::{ApiResponse, Object, OpenApi};
+use poem_openapi::param::{Path, Query};
+use poem_openapi::payload::Json;
+use poem_openapi::{Object, OpenApi};
 use tokio_stream::StreamExt;
 
 pub(crate) struct MenuApi;
@@ -13,6 +13,14 @@ pub(crate) struct MenuApi;
 const MENU_CREATE: &str = "insert into menu_info (parent_id, menu_type, menu_name, function_type, function_id) values (?, ?, ?, ?, ?)";
 const READ_MENU :&str = "select menu_id, parent_id, menu_type, menu_name, function_type, function_id, menu_order from menu_info where menu_id = ?";
 const READ_MENU_LIST :&str = "select menu_id, parent_id, menu_type, menu_name, function_type, function_id, menu_order from menu_info";
+const SEARCH_MENU_FULLTEXT: &str = "select menu_id, parent_id, menu_type, menu_name, page_id, table_name, menu_order from menu_info where match(menu_name) against (? in boolean mode) order by parent_id, menu_order";
+const SEARCH_MENU_LIKE: &str = "select menu_id, parent_id, menu_type, menu_name, page_id, table_name, menu_order from menu_info where menu_name like ? escape '\\\\' order by parent_id, menu_order";
+
+// boolean mode 的运算符，出现在词中间时也会被解析
+const FT_OPERATORS: &str = "+-<>()~*\"@";
+
+// innodb_ft_min_token_size 默认值，短于此长度的词不会进入全文索引
+const FT_MIN_TOKEN_SIZE: usize = 3;
 
 #[derive(Object, sqlx::FromRow)]
 struct Menu {
@@ -25,6 +33,18 @@ struct Menu {
     menu_order: i32,
 }
 
+/// 菜单检索结果，按 menu_info 的实际列返回
+#[derive(Object, sqlx::FromRow)]
+struct MenuItem {
+    menu_id: i32,
+    parent_id: i32,
+    menu_type: i32,
+    menu_name: String,
+    page_id: i32,
+    table_name: Option<String>,
+    menu_order: i32,
+}
+
 #[derive(Object)]
 struct MenuReq {
     update_menu: Option<MenuOpt>,
@@ -42,19 +62,10 @@ struct MenuOpt {
     menu_order: Option<i32>,
 }
 
-#[derive(ApiResponse)]
-enum Response {
-    #[oai(status = 200)]
-    Menu(Json<Menu>),
-
-    #[oai(status = 404)]
-    NotFound(PlainText<String>),
-}
-
 #[OpenApi(prefix_path = "/menu", tag = "ApiTags::Menu")]
 impl MenuApi {
-    #[oai(path = "/", method = "post")]
-    async fn create(&self, pool: Data<&DbPool>, menu: Json<Menu>) -> Result<Json<u64>> {
+    #[oai(path = "/", method = "post", operation_id = "menu_create")]
+    async fn create(&self, pool: Data<&DbPool>, menu: Json<Menu>) -> ApiResult<Json<u64>> {
         let menu = menu.0;
         let id = sqlx::query(MENU_CREATE)
             .bind(menu.parent_id)
@@ -63,42 +74,72 @@ impl MenuApi {
             .bind(menu.function_type)
             .bind(menu.function_id)
             .execute(pool.0)
-            .await
-            .map_err(InternalServerError)?
+            .await?
             .last_insert_id();
         Ok(Json(id))
     }
 
-    #[oai(path = "/:id", method = "get")]
-    async fn read(&self, pool: Data<&DbPool>, id: Path<u32>) -> Result<Response> {
+    #[oai(path = "/:id", method = "get", operation_id = "menu_read")]
+    async fn read(&self, pool: Data<&DbPool>, id: Path<u32>) -> ApiResult<Json<Menu>> {
         let menu: Option<Menu> = sqlx::query_as(READ_MENU)
             .bind(id.0)
             .fetch_optional(pool.0)
-            .await
-            .map_err(InternalServerError)?;
+            .await?;
 
         match menu {
-            Some(menu) => Ok(Response::Menu(Json(menu))),
-            None => Ok(Response::NotFound(PlainText(format!(
-                "Menu {} Not Found",
-                id.0
-            )))),
+            Some(menu) => Ok(Json(menu)),
+            None => Err(MenuNotFound(id.0).into()),
         }
     }
 
-    #[oai(path = "/", method = "get")]
-    async fn read_all(&self, pool: Data<&DbPool>) -> Result<Json<Vec<Menu>>> {
+    #[oai(path = "/", method = "get", operation_id = "menu_read_all")]
+    async fn read_all(&self, pool: Data<&DbPool>) -> ApiResult<Json<Vec<Menu>>> {
         let mut menus = Vec::new();
         let mut stream = sqlx::query_as::<_, Menu>(READ_MENU_LIST).fetch(pool.0);
         while let Some(res) = stream.next().await {
-            menus.push(res.map_err(InternalServerError)?);
+            menus.push(res?);
+        }
+
+        Ok(Json(menus))
+    }
+
+    #[oai(path = "/search", method = "get", operation_id = "menu_search")]
+    async fn search(
+        &self,
+        pool: Data<&DbPool>,
+        q: Query<String>,
+    ) -> ApiResult<Json<Vec<MenuItem>>> {
+        let q = q.0.trim();
+        if q.is_empty() {
+            return Ok(Json(Vec::new()));
+        }
+
+        let mut menus = Vec::new();
+        let fulltext = fulltext_term(q);
+        if let Some(term) = &fulltext {
+            let mut stream = sqlx::query_as::<_, MenuItem>(SEARCH_MENU_FULLTEXT)
+                .bind(term)
+                .fetch(pool.0);
+            while let Some(res) = stream.next().await {
+                menus.push(res?);
+            }
+        }
+
+        // 默认分词器不切分中文，短词也不入索引，此时退化为 like 匹配
+        if menus.is_empty() && (fulltext.is_none() || has_cjk(q)) {
+            let mut stream = sqlx::query_as::<_, MenuItem>(SEARCH_MENU_LIKE)
+                .bind(format!("%{}%", escape_like(q)))
+                .fetch(pool.0);
+            while let Some(res) = stream.next().await {
+                menus.push(res?);
+            }
         }
 
         Ok(Json(menus))
     }
 
-    #[oai(path = "/", method = "put")]
-    async fn update(&self, pool: Data<&DbPool>, menu_req: Json<MenuReq>) -> Result<Json<u64>> {
+    #[oai(path = "/", method = "put", operation_id = "menu_update")]
+    async fn update(&self, pool: Data<&DbPool>, menu_req: Json<MenuReq>) -> ApiResult<Json<u64>> {
         let mut sql = "update menu_info set ".to_string();
         if menu_req.update_menu.is_none() {
             return Ok(Json(0));
@@ -114,9 +155,8 @@ impl MenuApi {
 
         sql.pop();
 
-        if menu_req.where_menu.is_some() {
+        if let Some(where_menu) = &menu_req.where_menu {
             let mut and_str = " where ";
-            let where_menu = menu_req.where_menu.as_ref().unwrap();
 
             if where_menu.menu_id.is_some() {
                 sql += and_str;
@@ -132,35 +172,33 @@ impl MenuApi {
 
         let mut query = sqlx::query(&sql);
 
-        if update_menu.menu_name.is_some() {
-            query = query.bind(update_menu.menu_name.clone().unwrap());
+        if let Some(menu_name) = &update_menu.menu_name {
+            query = query.bind(menu_name.clone());
         }
-        if update_menu.menu_order.is_some() {
-            query = query.bind(update_menu.menu_order.unwrap());
+        if let Some(menu_order) = update_menu.menu_order {
+            query = query.bind(menu_order);
         }
 
-        if menu_req.where_menu.is_some() {
-            let where_menu = menu_req.where_menu.as_ref().unwrap();
-
-            if where_menu.menu_id.is_some() {
-                query = query.bind(where_menu.menu_id.unwrap());
+        if let Some(where_menu) = &menu_req.where_menu {
+            if let Some(menu_id) = where_menu.menu_id {
+                query = query.bind(menu_id);
             }
 
-            if where_menu.menu_order.is_some() {
-                query = query.bind(where_menu.menu_order.unwrap());
+            if let Some(menu_order) = where_menu.menu_order {
+                query = query.bind(menu_order);
             }
         }
 
-        let count = query
-            .execute(pool.0)
-            .await
-            .map_err(InternalServerError)?
-            .rows_affected();
+        let count = query.execute(pool.0).await?.rows_affected();
         Ok(Json(count))
     }
 
-    #[oai(path = "/", method = "delete")]
-    async fn delete(&self, pool: Data<&DbPool>, delete_menu: Json<MenuOpt>) -> Result<Json<u64>> {
+    #[oai(path = "/", method = "delete", operation_id = "menu_delete")]
+    async fn delete(
+        &self,
+        pool: Data<&DbPool>,
+        delete_menu: Json<MenuOpt>,
+    ) -> ApiResult<Json<u64>> {
         let mut sql = "delete from menu_info ".to_string();
         let mut and_str = " where ";
 
@@ -177,19 +215,91 @@ impl MenuApi {
 
         let mut query = sqlx::query(&sql);
 
-        if delete_menu.menu_id.is_some() {
-            query = query.bind(delete_menu.menu_id.unwrap());
+        if let Some(menu_id) = delete_menu.menu_id {
+            query = query.bind(menu_id);
         }
 
-        if delete_menu.menu_order.is_some() {
-            query = query.bind(delete_menu.menu_order.unwrap());
+        if let Some(menu_order) = delete_menu.menu_order {
+            query = query.bind(menu_order);
         }
 
-        let count = query
-            .execute(pool.0)
-            .await
-            .map_err(InternalServerError)?
-            .rows_affected();
+        let count = query.execute(pool.0).await?.rows_affected();
         Ok(Json(count))
     }
 }
+
+/// 生成 boolean mode 的检索式，每个词都必须出现且支持前缀匹配；没有可用的词时返回 None。
+/// 运算符按分隔符处理，如 `e-mail` 拆为 `e` 和 `mail`
+fn fulltext_term(q: &str) -> Option<String> {
+    let words = q
+        .split(|c: char| c.is_whitespace() || FT_OPERATORS.contains(c))
+        .filter(|w| w.chars().count() >= FT_MIN_TOKEN_SIZE)
+        .map(|w| format!("+{w}*"))
+        .collect::<Vec<_>>();
+
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+fn has_cjk(q: &str) -> bool {
+    q.chars().any(|c| {
+        matches!(c,
+            '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}')
+    })
+}
+
+fn escape_like(q: &str) -> String {
+    let mut s = String::with_capacity(q.len());
+    for c in q.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fulltext_term_requires_every_word() {
+        assert_eq!(
+            fulltext_term("user list"),
+            Some("+user* +list*".to_string())
+        );
+        assert_eq!(fulltext_term("  ab  "), None);
+    }
+
+    #[test]
+    fn fulltext_term_splits_on_operators() {
+        assert_eq!(
+            fulltext_term("user@mail"),
+            Some("+user* +mail*".to_string())
+        );
+        assert_eq!(fulltext_term("e-mail"), Some("+mail*".to_string()));
+        assert_eq!(
+            fulltext_term("\"(order)\" ~abc <def> ghi*"),
+            Some("+order* +abc* +def* +ghi*".to_string())
+        );
+        assert_eq!(fulltext_term("+-<>()~*\"@"), None);
+    }
+
+    #[test]
+    fn escape_like_escapes_wildcards() {
+        assert_eq!(escape_like("100%_a\\b"), "100\\%\\_a\\\\b");
+        assert_eq!(escape_like("菜单"), "菜单");
+    }
+
+    #[test]
+    fn has_cjk_detects_cjk_scripts() {
+        assert!(has_cjk("用户管理"));
+        assert!(has_cjk("menu メニュー"));
+        assert!(has_cjk("메뉴"));
+        assert!(!has_cjk("menu-list 123"));
+    }
+}
@@ -1,10 +1,22 @@
 use poem_openapi::Tags;
 
+pub(crate) mod admin;
 pub(crate) mod crud;
+pub(crate) mod dashboard;
+pub(crate) mod dict;
+pub(crate) mod export;
+pub(crate) mod import;
+pub(crate) mod layout;
 pub(crate) mod menu;
+pub(crate) mod view;
 
 #[derive(Tags)]
 enum ApiTags {
+    Admin,
     Crud,
+    Dashboard,
+    Dict,
+    Layout,
     Menu,
+    View,
 }
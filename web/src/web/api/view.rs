@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use poem::web::Data;
+use poem_openapi::param::{Path, Query};
+use poem_openapi::payload::Json;
+use poem_openapi::{Object, OpenApi};
+use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
+
+use crate::error::ApiResult;
+use crate::error::TransError::{CrudInfoNotFound, TooManyFields, ViewNotFound};
+use crate::web::api::crud::{sort_clause, SortKey};
+use crate::web::api::ApiTags;
+use crate::web::meta::cache;
+use crate::web::user::{self, owner_clause, OWNER_ROLE, OWNER_USER};
+use crate::web::DbPool;
+use crate::GLOBAL_CONFIG;
+
+pub(crate) struct ViewApi;
+
+const SQL_VIEWS: &str = "SELECT `view_id`,`owner_type`,`owner_id`,`view_name`,`settings` FROM saved_view WHERE `table_name` = ? AND ";
+const SQL_VIEW_CREATE: &str = "INSERT INTO saved_view (`owner_type`,`owner_id`,`table_name`,`view_name`,`settings`) VALUES (?, ?, ?, ?, ?)";
+const SQL_VIEW_UPDATE: &str = "UPDATE saved_view SET `owner_type` = ?,`owner_id` = ?,`view_name` = ?,`settings` = ? WHERE `view_id` = ? AND `table_name` = ? AND ";
+const SQL_VIEW_DELETE: &str = "DELETE FROM saved_view WHERE `view_id` = ? AND ";
+const SQL_VIEW_VISIBLE: &str = "SELECT COUNT(*) FROM saved_view WHERE `view_id` = ? AND ";
+const SQL_DEFAULT_VIEWS: &str =
+    "SELECT `owner_type`,`view_id` FROM default_view WHERE `page_id` = ? AND ";
+const SQL_DEFAULT_SET: &str = "INSERT INTO default_view (`owner_type`,`owner_id`,`page_id`,`view_id`) VALUES (?, ?, ?, ?) ON DUPLICATE KEY UPDATE `view_id` = VALUES(`view_id`)";
+const SQL_DEFAULT_CLEAR: &str =
+    "DELETE FROM default_view WHERE `owner_type` = ? AND `owner_id` = ? AND `page_id` = ?";
+const SQL_DEFAULT_DELETE_VIEW: &str = "DELETE FROM default_view WHERE `view_id` = ?";
+
+/// (编号, 所属类型, 用户或角色, 名称, 设置)
+type ViewRow = (u32, String, String, String, String);
+
+/// 视图保存的表格状态
+#[derive(Object, Serialize, Deserialize, Default)]
+struct ViewSettings {
+    /// 与 `/crud/read` 相同的 s 条件
+    conditions: HashMap<String, String>,
+    sort: Vec<SortKey>,
+    /// 显示的 r 列及顺序，为空时显示全部
+    columns: Vec<String>,
+    /// 每页行数，为空时使用前端的默认值
+    page_size: Option<u32>,
+}
+
+impl ViewSettings {
+    fn check_limits(&self) -> ApiResult<()> {
+        let cfg = GLOBAL_CONFIG.get().unwrap().load();
+        if self.columns.len() > cfg.limit.max_columns {
+            return Err(TooManyFields("columns".to_string(), cfg.limit.max_columns).into());
+        }
+        if self.sort.len() > cfg.limit.max_columns {
+            return Err(TooManyFields("sort".to_string(), cfg.limit.max_columns).into());
+        }
+        if self.conditions.len() > cfg.limit.max_conditions {
+            return Err(TooManyFields("conditions".to_string(), cfg.limit.max_conditions).into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Object)]
+struct View {
+    /// 新增时为空
+    view_id: Option<u32>,
+    /// 共享给该角色，为空时只属于当前用户
+    role: Option<String>,
+    table_name: String,
+    view_name: String,
+    settings: ViewSettings,
+}
+
+/// 当前用户在一张表上可用的视图
+#[derive(Object)]
+struct ViewList {
+    /// 当前用户，匿名时为空，只能使用不能保存视图
+    user_id: Option<String>,
+    /// 当前用户的角色，视图可以共享给其中之一
+    roles: Vec<String>,
+    views: Vec<View>,
+    /// page_id 对应菜单项的默认视图，本人设置的优先于角色设置的
+    default_view: Option<u32>,
+}
+
+#[derive(Object)]
+struct DefaultView {
+    /// 菜单项的页面编号，对应 menu_info.PAGE_ID
+    page_id: i32,
+    /// 为空时取消默认视图
+    view_id: Option<u32>,
+    /// 设置为该角色的默认视图，为空时只对当前用户生效
+    role: Option<String>,
+}
+
+#[OpenApi(prefix_path = "/view", tag = "ApiTags::View")]
+impl ViewApi {
+    /// 当前用户及其角色在该表上的视图，按名称排序
+    #[oai(path = "/:table_name", method = "get", operation_id = "view_list")]
+    async fn list(
+        &self,
+        pool: Data<&DbPool>,
+        table_name: Path<String>,
+        page_id: Query<Option<i32>>,
+    ) -> ApiResult<Json<ViewList>> {
+        let user = user::current();
+        let owners = user.owners();
+        let sql = format!(
+            "{SQL_VIEWS}{} ORDER BY `view_name`,`view_id`",
+            owner_clause(&owners)
+        );
+        let mut query = sqlx::query_as::<_, ViewRow>(&sql).bind(&table_name.0);
+        for (owner_type, owner_id) in &owners {
+            query = query.bind(*owner_type).bind(owner_id);
+        }
+
+        let mut views = Vec::new();
+        let mut stream = query.fetch(pool.0);
+        while let Some(res) = stream.next().await {
+            let (view_id, owner_type, owner_id, view_name, settings) = res?;
+            // 无法解析的设置按空设置返回，由用户重新保存
+            let settings = serde_json::from_str(&settings).unwrap_or_else(|e| {
+                tracing::warn!("视图{view_id}的设置无法解析：{e}");
+                ViewSettings::default()
+            });
+            views.push(View {
+                view_id: Some(view_id),
+                role: (owner_type == OWNER_ROLE).then_some(owner_id),
+                table_name: table_name.0.clone(),
+                view_name,
+                settings,
+            });
+        }
+        drop(stream);
+
+        let default_view = match page_id.0 {
+            Some(page_id) => {
+                let sql = format!("{SQL_DEFAULT_VIEWS}{}", owner_clause(&owners));
+                let mut query = sqlx::query_as::<_, (String, u32)>(&sql).bind(page_id);
+                for (owner_type, owner_id) in &owners {
+                    query = query.bind(*owner_type).bind(owner_id);
+                }
+                let mut defaults = query
+                    .fetch_all(pool.0)
+                    .await?
+                    .into_iter()
+                    .filter(|(_, view_id)| views.iter().any(|v| v.view_id == Some(*view_id)))
+                    .collect::<Vec<_>>();
+                defaults.sort_by_key(|(owner_type, _)| owner_type != OWNER_USER);
+                defaults.first().map(|(_, view_id)| *view_id)
+            }
+            None => None,
+        };
+
+        Ok(Json(ViewList {
+            user_id: user.id.clone(),
+            roles: user.roles.clone(),
+            views,
+            default_view,
+        }))
+    }
+
+    /// 新增或修改视图，返回视图编号；只能修改本人或所属角色的视图
+    #[oai(path = "/", method = "post", operation_id = "view_save")]
+    async fn save(&self, pool: Data<&DbPool>, view: Json<View>) -> ApiResult<Json<u32>> {
+        let view = view.0;
+        view.settings.check_limits()?;
+        let user = user::current();
+        let (owner_type, owner_id) = user.owner(view.role.as_deref())?;
+        let meta = match cache::table(pool.0, &view.table_name).await? {
+            Some(meta) if !meta.select_sql.is_empty() => meta,
+            _ => return Err(CrudInfoNotFound(view.table_name.clone(), "r".to_string()).into()),
+        };
+        // 与查询时相同的校验，避免保存无法使用的排序
+        sort_clause(&meta, &view.settings.sort, &mut String::new())?;
+
+        let settings = serde_json::to_string(&view.settings).unwrap_or_default();
+        let view_id = match view.view_id {
+            Some(view_id) => view_id,
+            None => {
+                let res = sqlx::query(SQL_VIEW_CREATE)
+                    .bind(owner_type)
+                    .bind(owner_id)
+                    .bind(view.table_name)
+                    .bind(view.view_name)
+                    .bind(settings)
+                    .execute(pool.0)
+                    .await?;
+                return Ok(Json(res.last_insert_id() as u32));
+            }
+        };
+
+        let owners = user.owners();
+        let sql = format!("{SQL_VIEW_UPDATE}{}", owner_clause(&owners));
+        let mut update = sqlx::query(&sql)
+            .bind(owner_type)
+            .bind(owner_id)
+            .bind(view.view_name)
+            .bind(settings)
+            .bind(view_id)
+            .bind(view.table_name);
+        for (owner_type, owner_id) in owners {
+            update = update.bind(owner_type).bind(owner_id);
+        }
+        if update.execute(pool.0).await?.rows_affected() == 0 {
+            return Err(ViewNotFound(view_id).into());
+        }
+
+        Ok(Json(view_id))
+    }
+
+    /// 删除视图，同时取消以它为默认视图的设置；与按表名查询的路径区分开
+    #[oai(path = "/id/:view_id", method = "delete", operation_id = "view_delete")]
+    async fn delete(&self, pool: Data<&DbPool>, view_id: Path<u32>) -> ApiResult<Json<u64>> {
+        let owners = user::current().owners();
+        let sql = format!("{SQL_VIEW_DELETE}{}", owner_clause(&owners));
+        let mut delete = sqlx::query(&sql).bind(view_id.0);
+        for (owner_type, owner_id) in owners {
+            delete = delete.bind(owner_type).bind(owner_id);
+        }
+
+        let mut tx = pool.0.begin().await?;
+        let res = delete.execute(&mut *tx).await?;
+        if res.rows_affected() == 0 {
+            return Err(ViewNotFound(view_id.0).into());
+        }
+        sqlx::query(SQL_DEFAULT_DELETE_VIEW)
+            .bind(view_id.0)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(Json(res.rows_affected()))
+    }
+
+    /// 设置或取消菜单项的默认视图，只能选择当前用户可见的视图
+    #[oai(path = "/default", method = "put", operation_id = "view_set_default")]
+    async fn set_default(
+        &self,
+        pool: Data<&DbPool>,
+        default: Json<DefaultView>,
+    ) -> ApiResult<Json<u64>> {
+        let user = user::current();
+        let (owner_type, owner_id) = user.owner(default.role.as_deref())?;
+        let view_id = match default.view_id {
+            Some(view_id) => view_id,
+            None => {
+                let res = sqlx::query(SQL_DEFAULT_CLEAR)
+                    .bind(owner_type)
+                    .bind(owner_id)
+                    .bind(default.page_id)
+                    .execute(pool.0)
+                    .await?;
+                return Ok(Json(res.rows_affected()));
+            }
+        };
+
+        let owners = user.owners();
+        let sql = format!("{SQL_VIEW_VISIBLE}{}", owner_clause(&owners));
+        let mut visible = sqlx::query_scalar::<_, i64>(&sql).bind(view_id);
+        for (owner_type, owner_id) in owners {
+            visible = visible.bind(owner_type).bind(owner_id);
+        }
+        if visible.fetch_one(pool.0).await? == 0 {
+            return Err(ViewNotFound(view_id).into());
+        }
+
+        let res = sqlx::query(SQL_DEFAULT_SET)
+            .bind(owner_type)
+            .bind(owner_id)
+            .bind(default.page_id)
+            .bind(view_id)
+            .execute(pool.0)
+            .await?;
+        Ok(Json(res.rows_affected()))
+    }
+}
@@ -0,0 +1,76 @@
+use std::env;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use poem::http::StatusCode;
+use poem::web::{Data, Json};
+use poem::{get, handler, IntoResponse, Response, Route};
+use serde_json::{json, Value};
+
+use crate::web::meta::cache;
+use crate::web::DbPool;
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+pub(crate) fn route() -> Route {
+    STARTED.get_or_init(Instant::now);
+    Route::new()
+        .at("/", get(status))
+        .at("/live", get(live))
+        .at("/ready", get(ready))
+}
+
+/// 进程存活
+#[handler]
+fn live() -> &'static str {
+    "OK"
+}
+
+/// 数据库可用且元数据已加载时才接收流量
+#[handler]
+async fn ready(pool: Data<&DbPool>) -> Response {
+    let db = db_ok(pool.0).await;
+    let meta = cache::stats().loaded_at.is_some();
+    let code = if db && meta {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    Json(json!({ "database": db, "metadata": meta }))
+        .with_status(code)
+        .into_response()
+}
+
+/// 详细运行状态
+#[handler]
+async fn status(pool: Data<&DbPool>) -> Json<Value> {
+    let pool = pool.0;
+    let meta = cache::stats();
+    let uptime = STARTED
+        .get()
+        .map(|s| s.elapsed().as_secs())
+        .unwrap_or_default();
+
+    Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "env": env::var("APP_ENV").unwrap_or("prd".into()),
+        "uptime_secs": uptime,
+        "database": {
+            "ok": db_ok(pool).await,
+            "size": pool.size(),
+            "idle": pool.num_idle(),
+            "max_connections": pool.options().get_max_connections(),
+        },
+        "metadata": {
+            "loaded_at": meta.loaded_at,
+            "tables": meta.tables,
+            "hits": meta.hits,
+            "misses": meta.misses,
+        },
+    }))
+}
+
+async fn db_ok(pool: &DbPool) -> bool {
+    sqlx::query("SELECT 1").execute(pool).await.is_ok()
+}
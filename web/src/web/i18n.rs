@@ -0,0 +1,314 @@
+use std::fmt::Display;
+
+use poem::http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE};
+use poem::http::HeaderValue;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) enum Locale {
+    #[default]
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    /// 按 q 值从高到低取第一个支持的语言，如 `en-US,en;q=0.9,zh-CN;q=0.8`
+    pub(crate) fn from_accept_language(header: &str) -> Option<Self> {
+        let mut languages = header
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.trim().split(';');
+                let tag = parts.next()?.trim().to_ascii_lowercase();
+                let q = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((tag, q))
+            })
+            .filter(|(_, q)| *q > 0.0)
+            .collect::<Vec<_>>();
+        // 稳定排序，q 值相同时保持原有顺序
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        languages
+            .iter()
+            .find_map(|(tag, _)| match tag.split('-').next() {
+                Some("zh") => Some(Locale::ZhCn),
+                Some("en") => Some(Locale::En),
+                _ => None,
+            })
+    }
+}
+
+/// 服务端提示信息，按键查找：(键, 中文, 英文)；`{name}` 为占位符
+const MESSAGES: &[(&str, &str, &str)] = &[
+    // 错误响应
+    (
+        "error.request_must_contain",
+        "请求中缺少`{field}`",
+        "Request must contain `{field}`",
+    ),
+    (
+        "error.crud_info_not_found",
+        "表[`{table_name}`]没有配置类型为[`{crud_type}`]的crud_info",
+        "crud_info for table_name[`{table_name}`] and crud_type[`{crud_type}`] is not found",
+    ),
+    (
+        "error.csrf_token_mismatch",
+        "CSRF令牌缺失或不匹配",
+        "CSRF token is missing or does not match",
+    ),
+    (
+        "error.too_many_fields",
+        "{field}不能超过{max}个",
+        "Request contains more than {max} {field}",
+    ),
+    (
+        "error.menu_not_found",
+        "菜单{menu_id}不存在",
+        "Menu {menu_id} not found",
+    ),
+    (
+        "error.dict_not_found",
+        "字典{dict_id}不存在",
+        "Dict {dict_id} not found",
+    ),
+    (
+        "error.too_many_requests",
+        "请求过于频繁，请{secs}秒后重试",
+        "Too many requests, retry after {secs} seconds",
+    ),
+    ("error.export", "导出失败", "Export failed"),
+    (
+        "error.invalid_import_file",
+        "导入文件无法解析：{reason}",
+        "Import file is invalid: {reason}",
+    ),
+    (
+        "error.import_job_not_found",
+        "导入任务{job_id}不存在",
+        "Import job {job_id} not found",
+    ),
+    (
+        "error.relation_not_found",
+        "表[`{table_name}`]与主表[`{parent_table}`]之间没有配置关联",
+        "Relation between table `{table_name}` and parent table `{parent_table}` is not found",
+    ),
+    (
+        "error.invalid_aggregate",
+        "列`{column}`不能用于该聚合",
+        "Column `{column}` cannot be used in this aggregate",
+    ),
+    (
+        "error.invalid_sort",
+        "列`{column}`不能用于排序",
+        "Column `{column}` cannot be used for sorting",
+    ),
+    (
+        "error.user_required",
+        "请求中缺少用户标识",
+        "Request must carry a user identity",
+    ),
+    (
+        "error.role_not_granted",
+        "当前用户没有角色`{role}`",
+        "Role `{role}` is not granted to the current user",
+    ),
+    (
+        "error.widget_not_found",
+        "仪表盘组件{widget_id}不存在",
+        "Widget {widget_id} not found",
+    ),
+    (
+        "error.view_not_found",
+        "视图{view_id}不存在",
+        "View {view_id} not found",
+    ),
+    ("error.duplicate_key", "记录已存在", "Record already exists"),
+    (
+        "error.row_is_referenced",
+        "记录已被其它数据引用",
+        "Record is referenced by other records",
+    ),
+    (
+        "error.no_referenced_row",
+        "引用的记录不存在",
+        "Referenced record does not exist",
+    ),
+    (
+        "error.database_busy",
+        "数据库繁忙，请重试",
+        "Database is busy, please retry",
+    ),
+    ("error.database", "数据库错误", "Database error"),
+    ("error.internal", "服务器内部错误", "Internal server error"),
+    ("error.not_found", "请求的资源不存在", "Resource not found"),
+    (
+        "error.method_not_allowed",
+        "不支持的请求方法",
+        "Method not allowed",
+    ),
+    (
+        "error.payload_too_large",
+        "请求体过大",
+        "Request body is too large",
+    ),
+    (
+        "error.unsupported_media_type",
+        "不支持的请求类型",
+        "Unsupported media type",
+    ),
+    // 图表中系列的名称
+    ("aggregate.count", "数量", "Count"),
+    ("aggregate.count_of", "{column}数量", "Count of {column}"),
+    ("aggregate.sum", "{column}合计", "Sum of {column}"),
+    ("aggregate.avg", "{column}平均值", "Average of {column}"),
+    ("aggregate.min", "{column}最小值", "Min of {column}"),
+    ("aggregate.max", "{column}最大值", "Max of {column}"),
+    // 导入校验
+    ("import.required", "{label}不能为空", "{label} is required"),
+    (
+        "import.invalid_value",
+        "{label}不是有效的{type}",
+        "{label} is not a valid {type}",
+    ),
+    (
+        "import.not_in_dict",
+        "{label}不在字典选项中",
+        "{label} is not a dictionary option",
+    ),
+];
+
+/// 按当前请求的语言取提示信息，并替换其中的占位符
+pub(crate) fn tr(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(current(), key, args)
+}
+
+fn translate(locale: Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = match MESSAGES.iter().find(|(k, ..)| *k == key) {
+        Some((_, zh, _)) if locale == Locale::ZhCn => *zh,
+        Some((_, _, en)) => *en,
+        None => {
+            tracing::warn!("缺少提示信息{key}");
+            return key.to_string();
+        }
+    };
+
+    // 只扫描一遍模板，参数中的花括号不会被再次替换
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let arg = rest.find('}').and_then(|end| {
+            let (_, value) = args.iter().find(|(name, _)| *name == &rest[1..end])?;
+            Some((end, value))
+        });
+        match arg {
+            Some((end, value)) => {
+                message.push_str(&value.to_string());
+                rest = &rest[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+tokio::task_local! {
+    static CURRENT: Locale;
+}
+
+/// 当前请求的语言，不在请求处理过程中时为默认语言
+pub(crate) fn current() -> Locale {
+    CURRENT.try_with(|l| *l).unwrap_or_default()
+}
+
+/// 根据 `Accept-Language` 选择提示信息和列描述的语言
+pub(crate) struct Localize;
+
+impl<E: Endpoint> Middleware<E> for Localize {
+    type Output = LocalizeEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        LocalizeEndpoint(ep)
+    }
+}
+
+pub(crate) struct LocalizeEndpoint<E>(E);
+
+impl<E: Endpoint> Endpoint for LocalizeEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let locale = req
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Locale::from_accept_language)
+            .unwrap_or_default();
+
+        // 需要放在 RequestId 外层，RequestId 在作用域内将错误转换为响应
+        let mut resp = CURRENT
+            .scope(locale, self.0.call(req))
+            .await?
+            .into_response();
+        resp.headers_mut()
+            .insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.code()));
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    /// 模板中的占位符，两种语言必须一致
+    fn placeholders(template: &str) -> HashSet<&str> {
+        template
+            .split('{')
+            .skip(1)
+            .filter_map(|s| s.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn messages_have_unique_keys_and_matching_placeholders() {
+        let mut keys = HashSet::new();
+        for (key, zh, en) in MESSAGES {
+            assert!(keys.insert(key), "重复的键{}", key);
+            assert_eq!(placeholders(zh), placeholders(en), "{}", key);
+        }
+    }
+
+    #[test]
+    fn translate_replaces_placeholders() {
+        let args: &[(&str, &dyn Display)] = &[("label", &"{type}"), ("type", &"日期")];
+        assert_eq!(
+            translate(Locale::ZhCn, "import.invalid_value", args),
+            "{type}不是有效的日期"
+        );
+        assert_eq!(
+            translate(
+                Locale::En,
+                "error.too_many_fields",
+                &[("field", &"columns"), ("max", &3)]
+            ),
+            "Request contains more than 3 columns"
+        );
+        assert_eq!(translate(Locale::En, "no.such.key", &[]), "no.such.key");
+    }
+}
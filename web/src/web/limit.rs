@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use arc_swap::access::Access;
+use poem::error::SizedLimitError;
+use poem::http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
+use poem::{Body, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+use crate::config::{Config, LimitCfg};
+use crate::error::TransError::TooManyRequests;
+use crate::web::user;
+use crate::GLOBAL_CONFIG;
+
+/// 长时间未访问的令牌桶会被清理
+const IDLE: Duration = Duration::from_secs(600);
+/// 清理的间隔，避免每次取令牌都遍历全部令牌桶
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets(Mutex<(HashMap<String, Bucket>, Instant)>);
+
+impl Default for Buckets {
+    fn default() -> Self {
+        Buckets(Mutex::new((HashMap::new(), Instant::now())))
+    }
+}
+
+impl Buckets {
+    /// 取一个令牌，不足时返回需要等待的时间
+    fn take(&self, key: &str, rate: f64, burst: u32) -> Option<Duration> {
+        self.take_at(key, rate, burst, Instant::now())
+    }
+
+    fn take_at(&self, key: &str, rate: f64, burst: u32, now: Instant) -> Option<Duration> {
+        let burst = f64::from(burst.max(1));
+        let mut guard = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let (buckets, swept) = &mut *guard;
+        if now.duration_since(*swept) >= SWEEP_INTERVAL {
+            buckets.retain(|_, b| now.duration_since(b.updated) < IDLE);
+            *swept = now;
+        }
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+/// 按 IP 和用户的令牌桶限流，超出时返回 429 并带上 Retry-After
+pub(crate) struct RateLimit;
+
+impl<E: Endpoint> Middleware<E> for RateLimit {
+    type Output = RateLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RateLimitEndpoint {
+            inner: ep,
+            ip: Buckets::default(),
+            user: Buckets::default(),
+        }
+    }
+}
+
+pub(crate) struct RateLimitEndpoint<E> {
+    inner: E,
+    ip: Buckets,
+    user: Buckets,
+}
+
+impl<E: Endpoint> RateLimitEndpoint<E> {
+    fn check(&self, req: &Request, cfg: &Config) -> Option<Duration> {
+        let (cfg, trusted_proxies) = (&cfg.limit, &cfg.auth.trusted_proxies);
+        if cfg.ip_rate > 0.0 {
+            let ip = user::client_ip(req, trusted_proxies)?;
+            let wait = self.ip.take(&ip.to_string(), cfg.ip_rate, cfg.ip_burst);
+            if wait.is_some() {
+                return wait;
+            }
+        }
+
+        // 用户标识取 Identify 识别的结果，只有可信网关设置的标识有效，同一用户在各地址共用令牌
+        if cfg.user_rate > 0.0 {
+            if let Some(id) = user::current().id {
+                return self.user.take(&id, cfg.user_rate, cfg.user_burst);
+            }
+        }
+        None
+    }
+}
+
+impl<E: Endpoint> Endpoint for RateLimitEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let cfg = GLOBAL_CONFIG.get().unwrap().load();
+        if let Some(wait) = self.check(&req, &cfg) {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            tracing::warn!("请求过于频繁，{retry_after}秒后重试");
+            return Err(TooManyRequests(retry_after).into());
+        }
+
+        Ok(self.inner.call(req).await?.into_response())
+    }
+}
+
+/// 限制请求体大小；没有 Content-Length 或经过压缩的请求体按上限读取解压后的内容
+pub(crate) struct BodyLimit;
+
+impl<E: Endpoint> Middleware<E> for BodyLimit {
+    type Output = BodyLimitEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        BodyLimitEndpoint(ep)
+    }
+}
+
+pub(crate) struct BodyLimitEndpoint<E>(E);
+
+impl<E: Endpoint> Endpoint for BodyLimitEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let is_upload = req
+            .content_type()
+            .is_some_and(|t| t.starts_with("multipart/form-data"));
+        let max_size = if is_upload {
+            limit_cfg().max_upload_size
+        } else {
+            limit_cfg().max_body_size
+        };
+        let content_length = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+
+        // Compression 已解压请求体，此时 Content-Length 是压缩后的长度
+        let encoded = req.headers().contains_key(CONTENT_ENCODING);
+
+        match content_length {
+            Some(len) if len > max_size => return Err(SizedLimitError::PayloadTooLarge.into()),
+            Some(_) if !encoded => {}
+            _ => {
+                let body = req.take_body().into_bytes_limit(max_size).await?;
+                req.set_body(Body::from_bytes(body));
+            }
+        }
+        self.0.call(req).await
+    }
+}
+
+fn limit_cfg() -> impl std::ops::Deref<Target = LimitCfg> {
+    GLOBAL_CONFIG
+        .get()
+        .unwrap()
+        .map(|cfg: &Config| &cfg.limit)
+        .load()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_allows_burst_then_waits() {
+        let buckets = Buckets::default();
+        for _ in 0..3 {
+            assert_eq!(buckets.take("a", 1.0, 3), None);
+        }
+        let wait = buckets.take("a", 1.0, 3).unwrap();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_secs(1));
+        // 不同的键使用各自的令牌桶
+        assert_eq!(buckets.take("b", 1.0, 3), None);
+    }
+
+    #[test]
+    fn take_refills_over_time() {
+        let buckets = Buckets::default();
+        let now = Instant::now();
+        assert_eq!(buckets.take_at("a", 2.0, 1, now), None);
+        assert_eq!(
+            buckets.take_at("a", 2.0, 1, now),
+            Some(Duration::from_millis(500))
+        );
+        let later = now + Duration::from_millis(500);
+        assert_eq!(buckets.take_at("a", 2.0, 1, later), None);
+    }
+
+    #[test]
+    fn take_sweeps_idle_buckets() {
+        let buckets = Buckets::default();
+        let now = Instant::now();
+        assert_eq!(buckets.take_at("a", 1.0, 1, now), None);
+        assert_eq!(buckets.take_at("b", 1.0, 1, now + IDLE), None);
+        let guard = buckets.0.lock().unwrap();
+        assert!(!guard.0.contains_key("a"));
+        assert!(guard.0.contains_key("b"));
+    }
+}
@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use poem_openapi::Object;
+use time::OffsetDateTime;
+use tokio_stream::StreamExt;
+
+use crate::web::DbPool;
+
+/// CRUD 元数据缓存，启动时加载，元数据变更或调用管理接口时整体替换
+static META_CACHE: OnceLock<ArcSwap<MetaCache>> = OnceLock::new();
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static RELOADS: AtomicU64 = AtomicU64::new(0);
+
+const SQL_META_ALL: &str =
+    "SELECT i.`table_name`,i.`crud_type`,i.`column_name`,i.`column_must`,t.`column_type`,t.`column_style`,t.`column_dict`,t.`column_desc` FROM crud_info i left join table_columns t on i.`table_name`=t.`table_name` and i.`column_name` = t.`column_name` order by i.`table_name`,i.`crud_type`,i.`column_order`";
+
+const SQL_META_TABLE: &str =
+    "SELECT i.`table_name`,i.`crud_type`,i.`column_name`,i.`column_must`,t.`column_type`,t.`column_style`,t.`column_dict`,t.`column_desc` FROM crud_info i left join table_columns t on i.`table_name`=t.`table_name` and i.`column_name` = t.`column_name` WHERE i.`table_name` = ? order by i.`crud_type`,i.`column_order`";
+
+const SQL_META_I18N_ALL: &str =
+    "SELECT `table_name`,`column_name`,`locale`,`column_desc` FROM table_columns_i18n";
+
+const SQL_META_I18N_TABLE: &str =
+    "SELECT `table_name`,`column_name`,`locale`,`column_desc` FROM table_columns_i18n WHERE `table_name` = ?";
+
+const SQL_META_RELATIONS_ALL: &str =
+    "SELECT `table_name`,`column_name`,`parent_table`,`parent_column`,`label_column` FROM table_relations";
+
+const SQL_META_RELATIONS_TABLE: &str =
+    "SELECT `table_name`,`column_name`,`parent_table`,`parent_column`,`label_column` FROM table_relations WHERE `table_name` = ? OR `parent_table` = ?";
+
+/// 不存在的表在这段时间内直接返回，不再查询数据库
+const MISSING_TTL: Duration = Duration::from_secs(30);
+/// 最多记录的不存在的表，超出时不再记录
+const MAX_MISSING: usize = 1024;
+
+/// 写入这些表后需要重新加载缓存
+pub(crate) const META_TABLES: [&str; 4] = [
+    "crud_info",
+    "table_columns",
+    "table_columns_i18n",
+    "table_relations",
+];
+
+/// 查询语句中当前表的别名，关联主表时避免列名冲突
+pub(crate) const TABLE_ALIAS: &str = "t";
+
+type MetaRow = (
+    String,
+    String,
+    String,
+    bool,
+    Option<String>,
+    Option<i32>,
+    Option<i32>,
+    Option<String>,
+);
+
+/// (表名, 列名, 语言, 列描述)
+type I18nRow = (String, String, String, String);
+
+/// (从表名, 外键列, 主表名, 主表关联列, 标签列)
+type RelationRow = (String, String, String, String, Option<String>);
+
+#[derive(Default)]
+struct MetaCache {
+    tables: HashMap<String, Arc<TableMeta>>,
+    /// 不存在的表及查询的时间
+    missing: HashMap<String, Instant>,
+    loaded_at: Option<OffsetDateTime>,
+    /// 每次整体加载加一，按表加载期间发生过整体加载时不写入缓存
+    generation: u64,
+}
+
+pub(crate) struct ColumnMeta {
+    pub(crate) column_name: String,
+    pub(crate) column_must: bool,
+    pub(crate) column_type: String,
+    pub(crate) column_style: Option<i32>,
+    pub(crate) column_dict: Option<i32>,
+    pub(crate) column_desc: Option<String>,
+    /// 语言 -> 列描述
+    column_desc_i18n: HashMap<String, String>,
+}
+
+impl ColumnMeta {
+    /// 指定语言的列描述，没有翻译时使用 table_columns.COLUMN_DESC
+    pub(crate) fn desc(&self, locale: &str) -> Option<&str> {
+        self.column_desc_i18n
+            .get(locale)
+            .map(String::as_str)
+            .or(self.column_desc.as_deref())
+    }
+
+    /// 导入文件的表头可以是列名，也可以是任一语言的列描述
+    pub(crate) fn matches(&self, header: &str) -> bool {
+        self.column_name.eq_ignore_ascii_case(header)
+            || self.column_desc.as_deref() == Some(header)
+            || self.column_desc_i18n.values().any(|d| d == header)
+    }
+}
+
+/// table_relations 中的一条主从关联
+#[derive(Object, Clone)]
+pub(crate) struct Relation {
+    /// 从表名
+    pub(crate) table_name: String,
+    /// 从表中引用主表的外键列
+    pub(crate) column_name: String,
+    pub(crate) parent_table: String,
+    pub(crate) parent_column: String,
+    /// 查询从表时 join 主表取出的标签列
+    pub(crate) label_column: Option<String>,
+}
+
+/// 单张表的元数据及预先拼好的 SQL 片段
+#[derive(Default)]
+pub(crate) struct TableMeta {
+    /// crud_type -> 按 column_order 排序的列
+    columns: HashMap<String, Vec<ColumnMeta>>,
+    /// 本表作为从表的关联
+    pub(crate) parents: Vec<Relation>,
+    /// 本表作为主表的关联
+    pub(crate) children: Vec<Relation>,
+    /// r 中的外键列 -> join 出的主表标签表达式
+    label_exprs: HashMap<String, String>,
+    /// ` FROM <表> t`
+    pub(crate) from_sql: String,
+    /// 在 from_sql 基础上 left join 有标签列的主表
+    pub(crate) join_sql: String,
+    /// `SELECT <r 列> FROM <表> t`，未配置 r 时为空
+    pub(crate) select_sql: String,
+    /// 在 select_sql 基础上 join 主表，为外键列追加 `<列名>_label`
+    pub(crate) label_sql: String,
+    /// `INSERT INTO <表>(`
+    pub(crate) insert_sql: String,
+    /// `UPDATE <表> SET `
+    pub(crate) update_sql: String,
+    /// `DELETE FROM <表>`
+    pub(crate) delete_sql: String,
+}
+
+impl TableMeta {
+    pub(crate) fn columns(&self, crud_type: &str) -> &[ColumnMeta] {
+        self.columns
+            .get(crud_type)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn column(&self, crud_type: &str, column_name: &str) -> Option<&ColumnMeta> {
+        self.columns(crud_type)
+            .iter()
+            .find(|c| c.column_name == column_name)
+    }
+
+    /// 列在任一 crud_type 中配置的类型
+    pub(crate) fn column_type(&self, column_name: &str) -> Option<&str> {
+        self.columns
+            .values()
+            .flatten()
+            .find(|c| c.column_name == column_name && !c.column_type.is_empty())
+            .map(|c| &*c.column_type)
+    }
+
+    /// 本表引用 parent_table 的关联
+    pub(crate) fn parent(&self, parent_table: &str) -> Option<&Relation> {
+        self.parents.iter().find(|r| r.parent_table == parent_table)
+    }
+
+    /// 引用本表的从表 table_name 的关联
+    pub(crate) fn child(&self, table_name: &str) -> Option<&Relation> {
+        self.children.iter().find(|r| r.table_name == table_name)
+    }
+
+    /// join_sql 中该列的主表标签表达式
+    pub(crate) fn label_expr(&self, column_name: &str) -> Option<&str> {
+        self.label_exprs.get(column_name).map(String::as_str)
+    }
+
+    /// label_sql 是否为该列 join 了主表的标签
+    pub(crate) fn has_label(&self, column_name: &str) -> bool {
+        self.label_exprs.contains_key(column_name)
+    }
+
+    /// `<列名>_label` 已被字典标签或 r 列占用
+    fn label_conflicts(&self, column_name: &str) -> bool {
+        let label = format!("{column_name}_label");
+        self.columns("r").iter().any(|c| {
+            (c.column_name == column_name && c.column_dict.is_some()) || c.column_name == label
+        })
+    }
+
+    fn compile(&mut self, table_name: &str) {
+        self.from_sql = format!(" FROM {table_name} {TABLE_ALIAS}");
+        self.join_sql = self.from_sql.clone();
+        for (i, relation) in self.parents.iter().enumerate() {
+            let label_column = match &relation.label_column {
+                Some(c) if self.column("r", &relation.column_name).is_some() => c,
+                _ => continue,
+            };
+            // 字典列的标签同样为 `<列名>_label`，同名的列也会被覆盖
+            if self.label_conflicts(&relation.column_name) {
+                tracing::warn!(
+                    "{table_name}.{}的主表标签与字典标签或同名列冲突，不加载",
+                    relation.column_name
+                );
+                continue;
+            }
+            self.label_exprs.insert(
+                relation.column_name.clone(),
+                format!("CAST(p{i}.{label_column} AS CHAR)"),
+            );
+            self.join_sql += &format!(
+                " LEFT JOIN {} p{i} ON p{i}.{} = {TABLE_ALIAS}.{}",
+                relation.parent_table, relation.parent_column, relation.column_name
+            );
+        }
+
+        let mut select_columns = self
+            .columns("r")
+            .iter()
+            .map(|c| format!("{TABLE_ALIAS}.{}", c.column_name))
+            .collect::<Vec<_>>();
+        if !select_columns.is_empty() {
+            self.select_sql = format!("SELECT {}{}", select_columns.join(","), self.from_sql);
+            for relation in &self.parents {
+                if let Some(expr) = self.label_exprs.get(&relation.column_name) {
+                    select_columns.push(format!("{expr} AS {}_label", relation.column_name));
+                }
+            }
+            self.label_sql = format!("SELECT {}{}", select_columns.join(","), self.join_sql);
+        }
+        self.insert_sql = format!("INSERT INTO {}(", table_name);
+        self.update_sql = format!("UPDATE {} SET ", table_name);
+        self.delete_sql = format!("DELETE FROM {}", table_name);
+    }
+}
+
+/// 缓存统计
+#[derive(Object)]
+pub(crate) struct MetaStats {
+    pub(crate) tables: u64,
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) reloads: u64,
+    pub(crate) loaded_at: Option<String>,
+}
+
+fn cache() -> &'static ArcSwap<MetaCache> {
+    META_CACHE.get_or_init(|| ArcSwap::from_pointee(MetaCache::default()))
+}
+
+fn build(
+    rows: Vec<MetaRow>,
+    i18n_rows: Vec<I18nRow>,
+    relation_rows: Vec<RelationRow>,
+) -> HashMap<String, Arc<TableMeta>> {
+    let mut descs: HashMap<(String, String), HashMap<String, String>> = HashMap::new();
+    for (table_name, column_name, locale, desc) in i18n_rows {
+        descs
+            .entry((table_name, column_name))
+            .or_default()
+            .insert(locale, desc);
+    }
+
+    let mut tables: HashMap<String, TableMeta> = HashMap::new();
+    for row in rows {
+        let (table_name, crud_type, column_name, column_must, column_type, style, dict, desc) = row;
+        let column_desc_i18n = descs
+            .get(&(table_name.clone(), column_name.clone()))
+            .cloned()
+            .unwrap_or_default();
+        tables
+            .entry(table_name)
+            .or_default()
+            .columns
+            .entry(crud_type)
+            .or_default()
+            .push(ColumnMeta {
+                column_name,
+                column_must,
+                column_type: column_type.unwrap_or_default(),
+                column_style: style,
+                column_dict: dict,
+                column_desc: desc,
+                column_desc_i18n,
+            });
+    }
+
+    // 关联同时挂到主表和从表上，没有配置 crud_info 的表不保留
+    for (table_name, column_name, parent_table, parent_column, label_column) in relation_rows {
+        let relation = Relation {
+            table_name,
+            column_name,
+            parent_table,
+            parent_column,
+            label_column,
+        };
+        if let Some(parent) = tables.get_mut(&relation.parent_table) {
+            // 嵌入从表时按主表查询结果中的关联列取值，关联列必须是主表的 r 列
+            if parent.column("r", &relation.parent_column).is_some() {
+                parent.children.push(relation.clone());
+            } else {
+                tracing::warn!(
+                    "关联{}.{}引用的{}.{}不是主表的r列，不能嵌入从表",
+                    relation.table_name,
+                    relation.column_name,
+                    relation.parent_table,
+                    relation.parent_column
+                );
+            }
+        }
+        if let Some(child) = tables.get_mut(&relation.table_name) {
+            child.parents.push(relation);
+        }
+    }
+
+    tables
+        .into_iter()
+        .map(|(table_name, mut meta)| {
+            meta.compile(&table_name);
+            (table_name, Arc::new(meta))
+        })
+        .collect()
+}
+
+/// 测试用：按 (crud_type, 列名, 列类型) 构造单张表的元数据
+#[cfg(test)]
+pub(crate) fn test_table(table_name: &str, columns: &[(&str, &str, &str)]) -> Arc<TableMeta> {
+    let rows = columns
+        .iter()
+        .map(|(crud_type, column_name, column_type)| {
+            (
+                table_name.to_string(),
+                crud_type.to_string(),
+                column_name.to_string(),
+                false,
+                Some(column_type.to_string()),
+                None,
+                None,
+                None,
+            )
+        })
+        .collect();
+    build(rows, Vec::new(), Vec::new())
+        .remove(table_name)
+        .unwrap_or_default()
+}
+
+/// 重新加载全部元数据，返回表的数量
+pub(crate) async fn reload(pool: &DbPool) -> sqlx::Result<usize> {
+    let mut rows = Vec::new();
+    let mut stream = sqlx::query_as::<_, MetaRow>(SQL_META_ALL).fetch(pool);
+    while let Some(res) = stream.next().await {
+        rows.push(res?);
+    }
+    let i18n_rows = sqlx::query_as::<_, I18nRow>(SQL_META_I18N_ALL)
+        .fetch_all(pool)
+        .await?;
+    let relation_rows = sqlx::query_as::<_, RelationRow>(SQL_META_RELATIONS_ALL)
+        .fetch_all(pool)
+        .await?;
+
+    let tables = build(rows, i18n_rows, relation_rows);
+    let count = tables.len();
+    cache().rcu(|c| MetaCache {
+        tables: tables.clone(),
+        missing: HashMap::new(),
+        loaded_at: Some(OffsetDateTime::now_utc()),
+        generation: c.generation + 1,
+    });
+    RELOADS.fetch_add(1, Ordering::Relaxed);
+    tracing::info!("CRUD元数据已加载，共{count}张表");
+    Ok(count)
+}
+
+/// 写入元数据表后重新加载缓存，失败时仅记录日志
+pub(crate) async fn reload_if_meta(pool: &DbPool, table_name: &str) {
+    if META_TABLES
+        .iter()
+        .any(|t| t.eq_ignore_ascii_case(table_name))
+    {
+        if let Err(e) = reload(pool).await {
+            tracing::error!("CRUD元数据重新加载失败：{e}");
+        }
+    }
+}
+
+/// 读取表的元数据，缓存中没有时尝试从数据库加载该表
+pub(crate) async fn table(pool: &DbPool, table_name: &str) -> sqlx::Result<Option<Arc<TableMeta>>> {
+    let cached = cache().load();
+    if let Some(meta) = cached.tables.get(table_name) {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        return Ok(Some(Arc::clone(meta)));
+    }
+    if cached
+        .missing
+        .get(table_name)
+        .is_some_and(|at| at.elapsed() < MISSING_TTL)
+    {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        return Ok(None);
+    }
+    let generation = cached.generation;
+    drop(cached);
+    MISSES.fetch_add(1, Ordering::Relaxed);
+
+    let mut rows = Vec::new();
+    let mut stream = sqlx::query_as::<_, MetaRow>(SQL_META_TABLE)
+        .bind(table_name)
+        .fetch(pool);
+    while let Some(res) = stream.next().await {
+        rows.push(res?);
+    }
+    let i18n_rows = sqlx::query_as::<_, I18nRow>(SQL_META_I18N_TABLE)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await?;
+    let relation_rows = sqlx::query_as::<_, RelationRow>(SQL_META_RELATIONS_TABLE)
+        .bind(table_name)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await?;
+
+    let meta = build(rows, i18n_rows, relation_rows).remove(table_name);
+    cache().rcu(|c| {
+        let mut tables = c.tables.clone();
+        let mut missing = c.missing.clone();
+        // 期间整体加载过时以整体加载的结果为准
+        if c.generation == generation {
+            match &meta {
+                Some(meta) => {
+                    tables.insert(table_name.to_string(), Arc::clone(meta));
+                    missing.remove(table_name);
+                }
+                None => {
+                    if missing.len() >= MAX_MISSING {
+                        missing.retain(|_, at| at.elapsed() < MISSING_TTL);
+                    }
+                    if missing.len() < MAX_MISSING {
+                        missing.insert(table_name.to_string(), Instant::now());
+                    }
+                }
+            }
+        }
+        MetaCache {
+            tables,
+            missing,
+            loaded_at: c.loaded_at,
+            generation: c.generation,
+        }
+    });
+    Ok(meta)
+}
+
+pub(crate) fn stats() -> MetaStats {
+    let cache = cache().load();
+    MetaStats {
+        tables: cache.tables.len() as u64,
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        reloads: RELOADS.load(Ordering::Relaxed),
+        loaded_at: cache.loaded_at.map(|t| t.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(table_name: &str, crud_type: &str, column_name: &str, dict: Option<i32>) -> MetaRow {
+        (
+            table_name.to_string(),
+            crud_type.to_string(),
+            column_name.to_string(),
+            false,
+            Some("INT".to_string()),
+            None,
+            dict,
+            None,
+        )
+    }
+
+    fn relation(column_name: &str, parent_column: &str) -> RelationRow {
+        (
+            "order_item".to_string(),
+            column_name.to_string(),
+            "order_info".to_string(),
+            parent_column.to_string(),
+            Some("order_no".to_string()),
+        )
+    }
+
+    #[test]
+    fn build_skips_children_without_r_parent_column() {
+        let rows = vec![
+            row("order_info", "r", "order_id", None),
+            row("order_info", "s", "buyer_id", None),
+            row("order_item", "r", "order_id", None),
+            row("order_item", "r", "buyer_id", None),
+        ];
+        let relations = vec![
+            relation("order_id", "order_id"),
+            relation("buyer_id", "buyer_id"),
+        ];
+        let tables = build(rows, Vec::new(), relations);
+
+        let parent = &tables["order_info"];
+        assert_eq!(parent.children.len(), 1);
+        assert_eq!(parent.children[0].parent_column, "order_id");
+        // 从表仍可 join 主表取标签
+        assert_eq!(tables["order_item"].parents.len(), 2);
+    }
+
+    #[test]
+    fn build_skips_labels_conflicting_with_dict() {
+        let rows = vec![
+            row("order_info", "r", "order_id", None),
+            row("order_item", "r", "order_id", Some(1)),
+            row("order_item", "r", "buyer_id", None),
+            row("order_item", "r", "buyer_id_label", None),
+            row("order_item", "r", "item_id", None),
+        ];
+        let relations = vec![
+            relation("order_id", "order_id"),
+            relation("buyer_id", "order_id"),
+            relation("item_id", "order_id"),
+        ];
+        let child = &build(rows, Vec::new(), relations)["order_item"];
+
+        assert!(!child.has_label("order_id"));
+        assert!(!child.has_label("buyer_id"));
+        assert!(child.has_label("item_id"));
+    }
+}
@@ -0,0 +1,215 @@
+use poem_openapi::Object;
+use tokio_stream::StreamExt;
+
+use crate::web::api::crud::{COLUMN_TYPES, CRUD_TYPES};
+use crate::web::DbPool;
+
+const SQL_CRUD_TYPES: &str = "SELECT DISTINCT `table_name`, `crud_type` FROM crud_info";
+
+const SQL_CRUD_WITHOUT_COLUMN: &str =
+    "SELECT i.`table_name`,i.`crud_type`,i.`column_name` FROM crud_info i left join table_columns t on i.`table_name`=t.`table_name` and i.`column_name` = t.`column_name` WHERE t.`column_name` is null order by i.`table_name`,i.`crud_type`,i.`column_order`";
+
+const SQL_TABLE_COLUMNS: &str =
+    "SELECT t.`table_name`,t.`column_name`,t.`column_type`,CAST(c.`DATA_TYPE` AS CHAR),CAST(c.`COLUMN_TYPE` AS CHAR) FROM table_columns t left join information_schema.columns c on c.`TABLE_SCHEMA` = database() and c.`TABLE_NAME` = t.`table_name` and c.`COLUMN_NAME` = t.`column_name` order by t.`table_name`,t.`column_name`";
+
+const SQL_DICT_NOT_FOUND: &str =
+    "SELECT t.`table_name`,t.`column_name`,t.`column_dict` FROM table_columns t WHERE t.`column_dict` is not null and not exists (select 1 from dict_info d where d.`dict_id` = t.`column_dict`)";
+
+const SQL_RELATION_NOT_IN_SCHEMA: &str =
+    "SELECT r.`table_name`,r.`column_name`,r.`parent_table`,r.`parent_column`,r.`label_column` FROM table_relations r WHERE not exists (select 1 from information_schema.columns c where c.`TABLE_SCHEMA` = database() and c.`TABLE_NAME` = r.`table_name` and c.`COLUMN_NAME` = r.`column_name`) or not exists (select 1 from information_schema.columns c where c.`TABLE_SCHEMA` = database() and c.`TABLE_NAME` = r.`parent_table` and c.`COLUMN_NAME` = r.`parent_column`) or (r.`label_column` is not null and not exists (select 1 from information_schema.columns c where c.`TABLE_SCHEMA` = database() and c.`TABLE_NAME` = r.`parent_table` and c.`COLUMN_NAME` = r.`label_column`))";
+
+/// 元数据不一致项
+#[derive(Object, Debug)]
+pub(crate) struct Inconsistency {
+    /// INVALID_CRUD_TYPE, COLUMN_NOT_DEFINED, UNKNOWN_COLUMN_TYPE, COLUMN_NOT_IN_SCHEMA, COLUMN_TYPE_MISMATCH, DICT_NOT_FOUND, RELATION_NOT_IN_SCHEMA
+    pub(crate) kind: String,
+    pub(crate) table_name: String,
+    pub(crate) column_name: Option<String>,
+    pub(crate) message: String,
+}
+
+impl Inconsistency {
+    fn new(kind: &str, table_name: String, column_name: Option<String>, message: String) -> Self {
+        Inconsistency {
+            kind: kind.to_string(),
+            table_name,
+            column_name,
+            message,
+        }
+    }
+}
+
+/// 校验 crud_info、table_columns、table_relations 与 information_schema 是否一致，返回全部不一致项
+pub(crate) async fn check(pool: &DbPool) -> sqlx::Result<Vec<Inconsistency>> {
+    let mut report = Vec::new();
+
+    let mut stream = sqlx::query_as::<_, (String, String)>(SQL_CRUD_TYPES).fetch(pool);
+    while let Some(res) = stream.next().await {
+        let (table_name, crud_type) = res?;
+        if !CRUD_TYPES.contains(&&*crud_type) {
+            report.push(Inconsistency::new(
+                "INVALID_CRUD_TYPE",
+                table_name,
+                None,
+                format!("crud_type `{crud_type}` is not one of {CRUD_TYPES:?}"),
+            ));
+        }
+    }
+
+    let mut stream =
+        sqlx::query_as::<_, (String, String, String)>(SQL_CRUD_WITHOUT_COLUMN).fetch(pool);
+    while let Some(res) = stream.next().await {
+        let (table_name, crud_type, column_name) = res?;
+        report.push(Inconsistency::new(
+            "COLUMN_NOT_DEFINED",
+            table_name,
+            Some(column_name),
+            format!("crud_info column of crud_type `{crud_type}` is missing in table_columns"),
+        ));
+    }
+
+    let mut stream = sqlx::query_as::<_, (String, String, String, Option<String>, Option<String>)>(
+        SQL_TABLE_COLUMNS,
+    )
+    .fetch(pool);
+    while let Some(res) = stream.next().await {
+        let (table_name, column_name, column_type, data_type, db_column_type) = res?;
+        if !COLUMN_TYPES.contains(&&*column_type) {
+            report.push(Inconsistency::new(
+                "UNKNOWN_COLUMN_TYPE",
+                table_name.clone(),
+                Some(column_name.clone()),
+                format!("column_type `{column_type}` is not supported"),
+            ));
+        }
+
+        match (data_type, db_column_type) {
+            (Some(data_type), Some(db_column_type)) => {
+                let unsigned = db_column_type.to_lowercase().contains("unsigned");
+                if COLUMN_TYPES.contains(&&*column_type)
+                    && !compatible(&column_type, &data_type.to_lowercase(), unsigned)
+                {
+                    report.push(Inconsistency::new(
+                        "COLUMN_TYPE_MISMATCH",
+                        table_name,
+                        Some(column_name),
+                        format!(
+                            "column_type `{column_type}` does not match database type `{db_column_type}`"
+                        ),
+                    ));
+                }
+            }
+            _ => report.push(Inconsistency::new(
+                "COLUMN_NOT_IN_SCHEMA",
+                table_name,
+                Some(column_name),
+                "column does not exist in information_schema".to_string(),
+            )),
+        }
+    }
+
+    let mut stream = sqlx::query_as::<_, (String, String, i32)>(SQL_DICT_NOT_FOUND).fetch(pool);
+    while let Some(res) = stream.next().await {
+        let (table_name, column_name, column_dict) = res?;
+        report.push(Inconsistency::new(
+            "DICT_NOT_FOUND",
+            table_name,
+            Some(column_name),
+            format!("column_dict `{column_dict}` does not exist in dict_info"),
+        ));
+    }
+
+    let mut stream = sqlx::query_as::<_, (String, String, String, String, Option<String>)>(
+        SQL_RELATION_NOT_IN_SCHEMA,
+    )
+    .fetch(pool);
+    while let Some(res) = stream.next().await {
+        let (table_name, column_name, parent_table, parent_column, label_column) = res?;
+        let label = label_column
+            .map(|c| format!(" (label `{c}`)"))
+            .unwrap_or_default();
+        report.push(Inconsistency::new(
+            "RELATION_NOT_IN_SCHEMA",
+            table_name,
+            Some(column_name),
+            format!(
+                "relation to `{parent_table}`.`{parent_column}`{label} refers to a column missing in information_schema"
+            ),
+        ));
+    }
+
+    Ok(report)
+}
+
+/// COLUMN_TYPE 与数据库实际类型是否可以互相转换
+fn compatible(column_type: &str, data_type: &str, unsigned: bool) -> bool {
+    let sign_matches = column_type.ends_with("_UNSIGNED") == unsigned;
+    match column_type.trim_end_matches("_UNSIGNED") {
+        "BIGINT" => data_type == "bigint" && sign_matches,
+        "INT" => matches!(data_type, "int" | "mediumint") && sign_matches,
+        "SMALLINT" => data_type == "smallint" && sign_matches,
+        "TINYINT" => data_type == "tinyint" && sign_matches,
+        "BOOLEAN" => matches!(data_type, "tinyint" | "bit"),
+        "BLOB" => matches!(
+            data_type,
+            "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary"
+        ),
+        "DATE" => data_type == "date",
+        "DATETIME" => matches!(data_type, "datetime" | "timestamp"),
+        "DECIMAL" => data_type == "decimal",
+        "DOUBLE" => data_type == "double",
+        "FLOAT" => data_type == "float",
+        "TIME" => data_type == "time",
+        "TEXT" => matches!(
+            data_type,
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum" | "set"
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compatible_checks_sign() {
+        assert!(compatible("INT", "int", false));
+        assert!(compatible("INT", "mediumint", false));
+        assert!(!compatible("INT", "int", true));
+        assert!(compatible("INT_UNSIGNED", "int", true));
+        assert!(!compatible("BIGINT_UNSIGNED", "bigint", false));
+        // BOOLEAN 不区分有无符号
+        assert!(compatible("BOOLEAN", "tinyint", true));
+        assert!(compatible("BOOLEAN", "bit", false));
+    }
+
+    #[test]
+    fn compatible_checks_type_family() {
+        assert!(compatible("TEXT", "varchar", false));
+        assert!(compatible("TEXT", "enum", false));
+        assert!(compatible("BLOB", "varbinary", false));
+        assert!(compatible("DATETIME", "timestamp", false));
+        assert!(!compatible("DATE", "datetime", false));
+        assert!(!compatible("TEXT", "int", false));
+        assert!(!compatible("UNKNOWN", "int", false));
+    }
+
+    #[test]
+    fn compatible_accepts_every_column_type() {
+        let data_types = [
+            "bigint", "int", "smallint", "tinyint", "blob", "date", "datetime", "decimal",
+            "double", "float", "time", "varchar",
+        ];
+        for column_type in COLUMN_TYPES {
+            let unsigned = column_type.ends_with("_UNSIGNED");
+            assert!(
+                data_types
+                    .iter()
+                    .any(|data_type| compatible(column_type, data_type, unsigned)),
+                "{}",
+                column_type
+            );
+        }
+    }
+}
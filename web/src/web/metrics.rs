@@ -0,0 +1,168 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use poem::web::Data;
+use poem::{handler, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use poem_openapi::OperationId;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::error::TransError;
+use crate::web::meta::cache;
+use crate::web::DbPool;
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    crud_operations: IntCounterVec,
+    errors: IntCounterVec,
+    pool_size: IntGauge,
+    pool_idle: IntGauge,
+    pool_max: IntGauge,
+    meta_hits: IntCounter,
+    meta_misses: IntCounter,
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+        let m = Metrics {
+            http_requests: IntCounterVec::new(
+                Opts::new("http_requests_total", "HTTP requests"),
+                &["method", "route", "status"],
+            )?,
+            http_duration: HistogramVec::new(
+                HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+                &["method", "route"],
+            )?,
+            crud_operations: IntCounterVec::new(
+                Opts::new("crud_operations_total", "CRUD operations per table"),
+                &["table", "operation"],
+            )?,
+            errors: IntCounterVec::new(
+                Opts::new("errors_total", "Responses with a 4xx or 5xx status"),
+                &["kind"],
+            )?,
+            pool_size: IntGauge::new("db_pool_connections", "Open database connections")?,
+            pool_idle: IntGauge::new("db_pool_idle_connections", "Idle database connections")?,
+            pool_max: IntGauge::new("db_pool_max_connections", "Maximum database connections")?,
+            meta_hits: IntCounter::new("crud_meta_cache_hits_total", "CRUD metadata cache hits")?,
+            meta_misses: IntCounter::new(
+                "crud_meta_cache_misses_total",
+                "CRUD metadata cache misses",
+            )?,
+            registry,
+        };
+
+        m.registry.register(Box::new(m.http_requests.clone()))?;
+        m.registry.register(Box::new(m.http_duration.clone()))?;
+        m.registry.register(Box::new(m.crud_operations.clone()))?;
+        m.registry.register(Box::new(m.errors.clone()))?;
+        m.registry.register(Box::new(m.pool_size.clone()))?;
+        m.registry.register(Box::new(m.pool_idle.clone()))?;
+        m.registry.register(Box::new(m.pool_max.clone()))?;
+        m.registry.register(Box::new(m.meta_hits.clone()))?;
+        m.registry.register(Box::new(m.meta_misses.clone()))?;
+        Ok(m)
+    }
+}
+
+fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("指标注册失败"))
+}
+
+/// 记录一次 CRUD 操作
+pub(crate) fn crud_operation(table_name: &str, operation: &str) {
+    metrics()
+        .crud_operations
+        .with_label_values(&[table_name, operation])
+        .inc();
+}
+
+/// Prometheus 文本格式的指标
+#[handler]
+pub(crate) fn export(pool: Data<&DbPool>) -> Result<String> {
+    let m = metrics();
+    m.pool_size.set(pool.0.size() as i64);
+    m.pool_idle.set(pool.0.num_idle() as i64);
+    m.pool_max
+        .set(pool.0.options().get_max_connections() as i64);
+    // 缓存的计数只增不减，按差值累加到计数器
+    let stats = cache::stats();
+    m.meta_hits
+        .inc_by(stats.hits.saturating_sub(m.meta_hits.get()));
+    m.meta_misses
+        .inc_by(stats.misses.saturating_sub(m.meta_misses.get()));
+
+    let mut buf = Vec::new();
+    TextEncoder::new()
+        .encode(&m.registry.gather(), &mut buf)
+        .map_err(poem::error::InternalServerError)?;
+    String::from_utf8(buf).map_err(poem::error::InternalServerError)
+}
+
+/// 统计请求数、耗时和错误类型
+pub(crate) struct HttpMetrics;
+
+impl<E: Endpoint> Middleware<E> for HttpMetrics {
+    type Output = HttpMetricsEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        HttpMetricsEndpoint(ep)
+    }
+}
+
+pub(crate) struct HttpMetricsEndpoint<E>(E);
+
+impl<E: Endpoint> Endpoint for HttpMetricsEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let method = req.method().to_string();
+        let path = req.uri().path().to_string();
+        let start = Instant::now();
+        let res = self.0.call(req).await.map(IntoResponse::into_response);
+
+        let m = metrics();
+        let (status, operation, kind) = match &res {
+            Ok(resp) => (
+                resp.status(),
+                resp.data::<OperationId>().map(|o| o.0),
+                "Http",
+            ),
+            Err(e) => {
+                let kind = e
+                    .downcast_ref::<TransError>()
+                    .map(TransError::kind)
+                    .unwrap_or("Other");
+                (e.status(), e.data::<OperationId>().map(|o| o.0), kind)
+            }
+        };
+        // 按状态码统计，Cors 等中间件已转换为响应的错误也计入
+        if status.is_client_error() || status.is_server_error() {
+            m.errors.with_label_values(&[kind]).inc();
+        }
+        // 没有 operation_id 的路由按第一级路径归类，避免标签基数过大
+        let route = operation.map(str::to_string).unwrap_or_else(|| {
+            let first = path
+                .trim_start_matches('/')
+                .split('/')
+                .next()
+                .unwrap_or_default();
+            format!("/{first}")
+        });
+
+        m.http_requests
+            .with_label_values(&[&method, &route, status.as_str()])
+            .inc();
+        m.http_duration
+            .with_label_values(&[&method, &route])
+            .observe(start.elapsed().as_secs_f64());
+        res
+    }
+}
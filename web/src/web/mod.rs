@@ -1,24 +1,58 @@
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
-use poem::listener::TcpListener;
+use arc_swap::ArcSwap;
+
+use poem::listener::{BoxListener, Listener, TcpListener};
 use poem::middleware::{
-    AddData, CatchPanic, Compression, CookieJarManager, NormalizePath, Tracing, TrailingSlash,
+    CatchPanic, Compression, CookieJarManager, Cors, NormalizePath, Tracing, TrailingSlash,
 };
-use poem::{EndpointExt, Route, Server};
+use poem::{Endpoint, EndpointExt, Route, Server};
 use poem_openapi::OpenApiService;
+use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
 
+use crate::config::{MysqlCfg, WebCfg};
+use crate::web::api::admin::AdminApi;
 use crate::web::api::crud::CRUDApi;
+use crate::web::api::dashboard::DashboardApi;
+use crate::web::api::dict::DictApi;
+use crate::web::api::layout::LayoutApi;
 use crate::web::api::menu::MenuApi;
-use crate::GLOBAL_CONFIG;
+use crate::web::api::view::ViewApi;
+use crate::web::i18n::Localize;
+use crate::web::limit::{BodyLimit, RateLimit};
+use crate::web::metrics::HttpMetrics;
+use crate::web::request_id::{RequestId, REQUEST_ID};
+use crate::web::security::{Csrf, SecurityHeaders, CSRF_HEADER};
+use crate::web::static_files::StaticFiles;
+use crate::web::tls::HttpsRedirect;
+use crate::web::user::Identify;
+use crate::{config, error, GLOBAL_CONFIG};
 
 mod api;
+mod health;
+pub(crate) mod i18n;
+mod limit;
+mod meta;
+mod metrics;
+pub(crate) mod request_id;
+mod security;
+mod static_files;
+mod tls;
+pub(crate) mod user;
 
 pub(crate) type DbPool = sqlx::MySqlPool;
 
+/// 当前的连接池，mysql 配置修改后整体替换
+static POOL: OnceLock<ArcSwap<DbPool>> = OnceLock::new();
+
 //noinspection HttpUrlsUsage
 pub(crate) async fn start() {
     let cfg = GLOBAL_CONFIG.get().unwrap().load();
-    let pool = match DbPool::connect(&cfg.mysql.url).await {
+    let pool = match connect(&cfg.mysql).await {
         Ok(p) => p,
         Err(e) => {
             tracing::error!("数据库连接失败：{e}");
@@ -26,23 +60,91 @@ pub(crate) async fn start() {
         }
     };
 
-    let hero_service = OpenApiService::new((MenuApi, CRUDApi), "Hero", "1.0.0")
-        .server(format!("http://{}/api", cfg.web.address));
+    POOL.get_or_init(|| ArcSwap::from_pointee(pool.clone()));
+
+    if let Err(e) = meta::cache::reload(&pool).await {
+        tracing::error!("CRUD元数据加载失败：{e}");
+    }
+
+    if cfg.meta.check_on_start && !check_meta(&pool, cfg.meta.strict).await {
+        return;
+    }
+
+    tokio::spawn(config::watch());
+
+    let hero_service = OpenApiService::new(
+        (
+            MenuApi,
+            CRUDApi,
+            DictApi,
+            DashboardApi,
+            ViewApi,
+            LayoutApi,
+            AdminApi,
+        ),
+        "Hero",
+        "1.0.0",
+    )
+    .server(format!("{}://{}/api", cfg.web.scheme(), cfg.web.address));
     let swagger_ui = hero_service.swagger_ui();
     let spec = hero_service.spec();
 
-    let route = Route::new()
-        .nest("/api", hero_service)
+    let mut route = Route::new()
+        .nest(
+            "/api",
+            hero_service
+                .with(BodyLimit)
+                .with(RateLimit)
+                .with(Identify)
+                .after(|res| async move { res.map_err(error::wrap) }),
+        )
         .nest("/swagger", swagger_ui)
-        .at("/spec", poem::endpoint::make_sync(move |_| spec.clone()))
-        .with(AddData::new(pool))
+        .nest("/health", health::route())
+        .at("/metrics", metrics::export)
+        .at("/spec", poem::endpoint::make_sync(move |_| spec.clone()));
+
+    if !cfg.web.static_dir.is_empty() {
+        if Path::new(&cfg.web.static_dir).is_dir() {
+            route = route.nest("/", StaticFiles::new(&cfg.web.static_dir));
+        } else {
+            tracing::warn!("静态文件目录 {} 不存在，不提供前端页面", cfg.web.static_dir);
+        }
+    }
+
+    let route = route
+        // 每个请求使用当时的连接池，替换后新请求不再使用旧连接池
+        .around(|ep, mut req| async move {
+            req.extensions_mut().insert(current_pool());
+            ep.call(req).await
+        })
         .with(NormalizePath::new(TrailingSlash::Trim))
+        .with_if(cfg.web.csrf, Csrf)
         .with(CookieJarManager::new())
         .with(Compression::new())
+        .with(SecurityHeaders::new(&cfg.web.csp))
+        .with_if(
+            !cfg.web.cors_origins.is_empty(),
+            cors(&cfg.web.cors_origins),
+        )
+        .with(HttpMetrics)
         .with(Tracing)
+        .with(RequestId)
+        .with(Localize)
+        .with_if(
+            !cfg.web.http_redirect.is_empty(),
+            HttpsRedirect::new(&cfg.web.address),
+        )
         .with(CatchPanic::new());
 
-    let res = Server::new(TcpListener::bind(&cfg.web.address))
+    let listener = match listener(&cfg.web) {
+        Ok(l) => l,
+        Err(e) => {
+            tracing::error!("证书加载失败：{e}");
+            return;
+        }
+    };
+
+    let res = Server::new(listener)
         .run_with_graceful_shutdown(route, ctrl_c(), Some(Duration::from_secs(10)))
         .await;
 
@@ -51,6 +153,113 @@ pub(crate) async fn start() {
     }
 }
 
+/// 按配置创建连接池，失败时按指数退避重试，以便数据库晚于服务启动
+async fn connect(cfg: &MysqlCfg) -> Result<DbPool, sqlx::Error> {
+    let options =
+        MySqlConnectOptions::from_str(&cfg.url)?.statement_cache_capacity(cfg.statement_cache_size);
+    let pool_options = MySqlPoolOptions::new()
+        .max_connections(cfg.max_connections)
+        .min_connections(cfg.min_connections)
+        .acquire_timeout(Duration::from_secs(cfg.acquire_timeout))
+        .idle_timeout((cfg.idle_timeout > 0).then(|| Duration::from_secs(cfg.idle_timeout)))
+        .max_lifetime((cfg.max_lifetime > 0).then(|| Duration::from_secs(cfg.max_lifetime)));
+
+    let mut backoff = cfg.retry_backoff;
+    let mut attempt = 0;
+    loop {
+        match pool_options.clone().connect_with(options.clone()).await {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt < cfg.connect_retries => {
+                attempt += 1;
+                tracing::warn!(
+                    "数据库连接失败，{backoff}ms 后第{attempt}/{}次重试：{e}",
+                    cfg.connect_retries
+                );
+                tokio::time::sleep(Duration::from_millis(backoff)).await;
+                backoff = (backoff * 2).min(cfg.max_retry_backoff);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn current_pool() -> DbPool {
+    DbPool::clone(&POOL.get().unwrap().load())
+}
+
+/// 按新的 mysql 配置重建连接池并替换，失败时继续使用当前连接池；
+/// 旧连接池在仍在使用它的请求和后台任务结束后释放
+pub(crate) async fn reconnect() {
+    let pool = match POOL.get() {
+        Some(pool) => pool,
+        None => return,
+    };
+    let cfg = GLOBAL_CONFIG.get().unwrap().load();
+    match connect(&cfg.mysql).await {
+        Ok(new) => {
+            pool.store(Arc::new(new));
+            tracing::info!("数据库连接池已按新配置重建");
+        }
+        Err(e) => tracing::error!("数据库连接池重建失败，继续使用当前连接池：{e}"),
+    }
+}
+
+/// 启动时校验元数据，strict 模式下存在不一致项则返回 false
+async fn check_meta(pool: &DbPool, strict: bool) -> bool {
+    let report = match meta::check::check(pool).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!("元数据校验失败：{e}");
+            return !strict;
+        }
+    };
+
+    for i in &report {
+        tracing::warn!(
+            "元数据不一致 [{}] {}.{}: {}",
+            i.kind,
+            i.table_name,
+            i.column_name.as_deref().unwrap_or("-"),
+            i.message
+        );
+    }
+
+    if strict && !report.is_empty() {
+        tracing::error!("元数据存在{}处不一致，拒绝启动", report.len());
+        return false;
+    }
+    true
+}
+
+/// 配置了证书时监听 HTTPS，并按需监听用于重定向的 HTTP 地址
+fn listener(cfg: &WebCfg) -> io::Result<BoxListener> {
+    if !cfg.tls() {
+        return Ok(TcpListener::bind(cfg.address.clone()).boxed());
+    }
+
+    let initial = tls::load(&cfg.tls_cert, &cfg.tls_key)?;
+    let https = TcpListener::bind(cfg.address.clone()).rustls(tls::watch(
+        cfg.tls_cert.clone(),
+        cfg.tls_key.clone(),
+        initial,
+    ));
+    if cfg.http_redirect.is_empty() {
+        Ok(https.boxed())
+    } else {
+        Ok(https
+            .combine(TcpListener::bind(cfg.http_redirect.clone()))
+            .boxed())
+    }
+}
+
+fn cors(origins: &[String]) -> Cors {
+    Cors::new()
+        .allow_origins(origins)
+        .allow_credentials(true)
+        .allow_headers(["content-type", CSRF_HEADER, REQUEST_ID])
+        .expose_header(REQUEST_ID)
+}
+
 async fn ctrl_c() {
     if let Err(e) = tokio::signal::ctrl_c().await {
         tracing::error!("退出信号异常: {}", e);
@@ -0,0 +1,69 @@
+use poem::http::HeaderValue;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub(crate) const REQUEST_ID: &str = "x-request-id";
+
+tokio::task_local! {
+    /// 当前请求的ID，用于错误响应
+    static CURRENT: String;
+}
+
+/// 当前请求的ID，不在请求处理过程中时为 None
+pub(crate) fn current() -> Option<String> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// 生成或沿用请求头中的 `X-Request-Id`，记录到请求的 span 并写回响应头
+pub(crate) struct RequestId;
+
+impl<E: Endpoint> Middleware<E> for RequestId {
+    type Output = RequestIdEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestIdEndpoint(ep)
+    }
+}
+
+pub(crate) struct RequestIdEndpoint<E>(E);
+
+impl<E: Endpoint> Endpoint for RequestIdEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID)
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| valid(id))
+            .map(str::to_string)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let span = tracing::info_span!("request", request_id = %request_id);
+        // 错误也在作用域内转换为响应，保证每个响应都带上请求ID
+        let handle = async {
+            match self.0.call(req).await {
+                Ok(resp) => resp.into_response(),
+                Err(e) => e.into_response(),
+            }
+        };
+        let mut resp = CURRENT
+            .scope(request_id.clone(), handle)
+            .instrument(span)
+            .await;
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            resp.headers_mut().insert(REQUEST_ID, value);
+        }
+        Ok(resp)
+    }
+}
+
+/// 只沿用长度合理且不含特殊字符的请求ID
+fn valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
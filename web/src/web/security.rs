@@ -0,0 +1,125 @@
+use poem::http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS,
+    X_FRAME_OPTIONS,
+};
+use poem::http::{HeaderValue, Method};
+use poem::web::cookie::{Cookie, SameSite};
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use uuid::Uuid;
+
+use crate::error::TransError;
+
+pub(crate) const CSRF_COOKIE: &str = "XSRF-TOKEN";
+pub(crate) const CSRF_HEADER: &str = "x-xsrf-token";
+
+/// Swagger UI 使用内联脚本，单独放宽
+const SWAGGER_CSP: &str = "default-src 'self'; script-src 'self' 'unsafe-inline'; style-src 'self' 'unsafe-inline'; img-src 'self' data:";
+
+/// 添加 CSP、X-Frame-Options 等安全响应头，HTTPS 请求额外添加 HSTS
+pub(crate) struct SecurityHeaders {
+    csp: HeaderValue,
+}
+
+impl SecurityHeaders {
+    pub(crate) fn new(csp: &str) -> Self {
+        let csp = HeaderValue::from_str(csp).unwrap_or_else(|e| {
+            tracing::warn!("web.csp 配置无效，使用默认值：{e}");
+            HeaderValue::from_static("default-src 'self'")
+        });
+        SecurityHeaders { csp }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for SecurityHeaders {
+    type Output = SecurityHeadersEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        SecurityHeadersEndpoint {
+            inner: ep,
+            csp: self.csp.clone(),
+        }
+    }
+}
+
+pub(crate) struct SecurityHeadersEndpoint<E> {
+    inner: E,
+    csp: HeaderValue,
+}
+
+impl<E: Endpoint> Endpoint for SecurityHeadersEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let https = req.scheme().as_str() == "https";
+        let csp = if req.uri().path().starts_with("/swagger") {
+            HeaderValue::from_static(SWAGGER_CSP)
+        } else {
+            self.csp.clone()
+        };
+
+        let mut resp = self.inner.call(req).await?.into_response();
+        let headers = resp.headers_mut();
+        headers.insert(CONTENT_SECURITY_POLICY, csp);
+        headers.insert(X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+        headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+        headers.insert(
+            REFERRER_POLICY,
+            HeaderValue::from_static("strict-origin-when-cross-origin"),
+        );
+        if https {
+            headers.insert(
+                STRICT_TRANSPORT_SECURITY,
+                HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+            );
+        }
+        Ok(resp)
+    }
+}
+
+/// 双重提交 Cookie 方式的 CSRF 防护：
+/// 下发 `XSRF-TOKEN` Cookie，修改类请求必须在 `X-XSRF-TOKEN` 请求头中带回相同的值
+pub(crate) struct Csrf;
+
+impl<E: Endpoint> Middleware<E> for Csrf {
+    type Output = CsrfEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        CsrfEndpoint(ep)
+    }
+}
+
+pub(crate) struct CsrfEndpoint<E>(E);
+
+impl<E: Endpoint> Endpoint for CsrfEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let token = req
+            .cookie()
+            .get(CSRF_COOKIE)
+            .map(|c| c.value_str().to_string());
+
+        let safe = matches!(
+            *req.method(),
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+        );
+        if !safe {
+            let header = req.headers().get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+            match (&token, header) {
+                (Some(token), Some(header)) if !token.is_empty() && token == header => {}
+                _ => return Err(TransError::CsrfTokenMismatch.into()),
+            }
+        }
+
+        if token.is_none() {
+            // 前端需要读取该 Cookie，因此不能设置 HttpOnly
+            let mut cookie = Cookie::new_with_str(CSRF_COOKIE, Uuid::new_v4().to_string());
+            cookie.set_path("/");
+            cookie.set_same_site(SameSite::Strict);
+            cookie.set_secure(req.scheme().as_str() == "https");
+            req.cookie().add(cookie);
+        }
+
+        self.0.call(req).await
+    }
+}
@@ -0,0 +1,91 @@
+use poem::endpoint::StaticFilesEndpoint;
+use poem::http::header::CACHE_CONTROL;
+use poem::http::HeaderValue;
+use poem::{Endpoint, IntoResponse, Request, Response, Result};
+
+/// 带哈希的资源文件内容不会变化，可以长期缓存
+const CACHE_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// index.html 等文件每次都需要向服务端确认
+const CACHE_NO_CACHE: &str = "no-cache";
+
+/// 提供 trunk 构建的前端文件，找不到页面路径时回退到 index.html 交给 yew_router 处理；
+/// 带扩展名的资源文件找不到时直接返回 404，避免把 index.html 当作资源缓存
+pub(crate) struct StaticFiles {
+    assets: StaticFilesEndpoint,
+    pages: StaticFilesEndpoint,
+}
+
+impl StaticFiles {
+    pub(crate) fn new(dir: &str) -> Self {
+        StaticFiles {
+            assets: StaticFilesEndpoint::new(dir),
+            pages: StaticFilesEndpoint::new(dir)
+                .index_file("index.html")
+                .fallback_to_index(),
+        }
+    }
+}
+
+impl Endpoint for StaticFiles {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let path = req.uri().path();
+        let hashed = is_hashed(path);
+        let mut resp = if hashed || is_asset(path) {
+            self.assets.call(req).await?.into_response()
+        } else {
+            self.pages.call(req).await?.into_response()
+        };
+        // 只有实际返回了带哈希的文件时才长期缓存
+        let cache = if hashed && resp.status().is_success() {
+            CACHE_IMMUTABLE
+        } else {
+            CACHE_NO_CACHE
+        };
+        resp.headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static(cache));
+        Ok(resp)
+    }
+}
+
+/// 最后一段带扩展名的路径视为资源文件，如 `/favicon.ico`
+fn is_asset(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    name.rsplit_once('.')
+        .is_some_and(|(stem, ext)| !stem.is_empty() && !ext.is_empty())
+}
+
+/// trunk 生成的文件名形如 `app-2c3b5e8f1a9d4c7e_bg.wasm`、`app-2c3b5e8f1a9d4c7e.js`
+fn is_hashed(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    let stem = name.split('.').next().unwrap_or_default();
+    stem.rsplit_once('-').is_some_and(|(_, hash)| {
+        let hash = hash.strip_suffix("_bg").unwrap_or(hash);
+        hash.len() >= 16 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_hashed_matches_trunk_names() {
+        assert!(is_hashed("/app-2c3b5e8f1a9d4c7e_bg.wasm"));
+        assert!(is_hashed("/static/app-2c3b5e8f1a9d4c7e.js"));
+        assert!(!is_hashed("/app-2c3b5e8f.js"));
+        assert!(!is_hashed("/app-2c3b5e8f1a9d4c7g.js"));
+        assert!(!is_hashed("/index.html"));
+        assert!(!is_hashed("/table/user-list"));
+    }
+
+    #[test]
+    fn is_asset_requires_extension() {
+        assert!(is_asset("/favicon.ico"));
+        assert!(is_asset("/static/app.css"));
+        assert!(!is_asset("/table/user_info"));
+        assert!(!is_asset("/"));
+        assert!(!is_asset("/.well-known"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use notify::{Event, RecursiveMode, Watcher};
+use poem::http::header::HOST;
+use poem::listener::{RustlsCertificate, RustlsConfig};
+use poem::web::Redirect;
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::Stream;
+
+/// 读取 PEM 格式的证书和私钥
+pub(crate) fn load(cert: &str, key: &str) -> io::Result<RustlsConfig> {
+    let config = RustlsConfig::new().fallback(
+        RustlsCertificate::new()
+            .cert(fs::read(cert)?)
+            .key(fs::read(key)?),
+    );
+    Ok(config)
+}
+
+/// 证书或私钥文件变更后重新读取，推送给 TLS 监听器，读取失败时继续使用旧证书
+pub(crate) fn watch(
+    cert: String,
+    key: String,
+    initial: RustlsConfig,
+) -> impl Stream<Item = RustlsConfig> + Send + 'static {
+    let (config_tx, config_rx) = mpsc::unbounded_channel();
+    config_tx.send(initial).ok();
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<()>();
+    let watched = [cert.clone(), key.clone()];
+    let watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res {
+            let changed = event
+                .paths
+                .iter()
+                .any(|p| watched.iter().any(|f| p.ends_with(file_name(f))));
+            if changed && (event.kind.is_modify() || event.kind.is_create()) {
+                tx.send(()).ok();
+            }
+        }
+    });
+    let mut watcher = match watcher {
+        Ok(w) => Some(w),
+        Err(e) => {
+            tracing::error!("证书文件监听创建失败: {e}");
+            None
+        }
+    };
+    // 证书更新时通常整体替换文件，因此监听所在目录
+    if let Some(w) = watcher.as_mut() {
+        for file in [&cert, &key] {
+            let dir = Path::new(file)
+                .parent()
+                .filter(|d| !d.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+            if let Err(e) = w.watch(dir, RecursiveMode::NonRecursive) {
+                tracing::error!("证书文件监听失败: {e}");
+            }
+        }
+    }
+
+    tokio::spawn(async move {
+        let _watcher = watcher;
+        while rx.recv().await.is_some() {
+            // 证书和私钥通常先后写入，等待两者都更新完成
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            while rx.try_recv().is_ok() {}
+            match load(&cert, &key) {
+                Ok(config) => {
+                    tracing::info!("证书文件已变更，重新加载");
+                    if config_tx.send(config).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => tracing::error!("证书重新加载失败，继续使用旧证书：{e}"),
+            }
+        }
+    });
+
+    UnboundedReceiverStream::new(config_rx)
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(path)
+}
+
+/// 将 HTTP 请求重定向到 HTTPS 端口
+pub(crate) struct HttpsRedirect {
+    port: Option<String>,
+}
+
+impl HttpsRedirect {
+    /// `address` 为 HTTPS 监听地址，端口为 443 时重定向地址中省略端口
+    pub(crate) fn new(address: &str) -> Self {
+        let port = address
+            .rsplit_once(':')
+            .map(|(_, port)| port.to_string())
+            .filter(|port| port != "443");
+        HttpsRedirect { port }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for HttpsRedirect {
+    type Output = HttpsRedirectEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        HttpsRedirectEndpoint {
+            inner: ep,
+            port: self.port.clone(),
+        }
+    }
+}
+
+pub(crate) struct HttpsRedirectEndpoint<E> {
+    inner: E,
+    port: Option<String>,
+}
+
+impl<E: Endpoint> Endpoint for HttpsRedirectEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if req.scheme().as_str() == "https" {
+            return Ok(self.inner.call(req).await?.into_response());
+        }
+
+        let host = req
+            .headers()
+            .get(HOST)
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or("localhost");
+        // 去掉 HTTP 端口，IPv6 地址形如 [::1]:80
+        let host = match host.rsplit_once(':') {
+            Some((h, port)) if !port.contains(']') => h,
+            _ => host,
+        };
+        let path = req
+            .uri()
+            .path_and_query()
+            .map(|p| p.as_str())
+            .unwrap_or("/");
+        let location = match &self.port {
+            Some(port) => format!("https://{host}:{port}{path}"),
+            None => format!("https://{host}{path}"),
+        };
+        Ok(Redirect::permanent(location).into_response())
+    }
+}
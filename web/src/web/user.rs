@@ -0,0 +1,260 @@
+use std::net::IpAddr;
+
+use poem::http::header::FORWARDED;
+use poem::http::HeaderMap;
+use poem::{Endpoint, Middleware, Request, Result};
+
+use crate::config::AuthCfg;
+use crate::error::TransError::{self, RoleNotGranted, UserRequired};
+use crate::GLOBAL_CONFIG;
+
+// 按用户或角色保存的数据的 OWNER_TYPE
+pub(crate) const OWNER_USER: &str = "u";
+pub(crate) const OWNER_ROLE: &str = "r";
+
+/// 网关认证后透传的当前用户，服务本身不做认证
+#[derive(Clone, Default, Debug)]
+pub(crate) struct User {
+    pub(crate) id: Option<String>,
+    pub(crate) roles: Vec<String>,
+}
+
+impl User {
+    /// 请求不是来自 `auth.trusted_proxies` 时为匿名用户，客户端自行设置的请求头不可信
+    fn from_request(req: &Request, cfg: &AuthCfg) -> Self {
+        let trusted = req
+            .remote_addr()
+            .as_socket_addr()
+            .is_some_and(|addr| is_trusted(addr.ip(), &cfg.trusted_proxies));
+        if !trusted {
+            return User::default();
+        }
+
+        let header = |name: &str| {
+            (!name.is_empty())
+                .then(|| req.headers().get(name))
+                .flatten()
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+        User {
+            id: header(&cfg.user_header).map(str::to_string),
+            roles: header(&cfg.role_header)
+                .map(|roles| {
+                    roles
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|r| !r.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 按用户保存的数据需要用户标识
+    pub(crate) fn require_id(&self) -> Result<&str, TransError> {
+        self.id.as_deref().ok_or(UserRequired)
+    }
+
+    /// 按角色保存的数据只能由该角色的用户修改
+    pub(crate) fn require_role(&self, role: &str) -> Result<(), TransError> {
+        if self.roles.iter().any(|r| r == role) {
+            Ok(())
+        } else {
+            Err(RoleNotGranted(role.to_string()))
+        }
+    }
+
+    /// 保存数据的所属，(所属类型, 用户或角色)；保存到角色时需要拥有该角色
+    pub(crate) fn owner(&self, role: Option<&str>) -> Result<(&'static str, String), TransError> {
+        match role {
+            Some(role) => {
+                self.require_role(role)?;
+                Ok((OWNER_ROLE, role.to_string()))
+            }
+            None => Ok((OWNER_USER, self.require_id()?.to_string())),
+        }
+    }
+
+    /// 本人及其各个角色，(所属类型, 用户或角色)
+    pub(crate) fn owners(&self) -> Vec<(&'static str, String)> {
+        self.id
+            .iter()
+            .map(|id| (OWNER_USER, id.clone()))
+            .chain(self.roles.iter().map(|role| (OWNER_ROLE, role.clone())))
+            .collect()
+    }
+}
+
+fn is_trusted(ip: IpAddr, trusted_proxies: &[String]) -> bool {
+    // IPv4 客户端经双栈监听时显示为 IPv4 映射的 IPv6 地址
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+        IpAddr::V4(_) => ip,
+    };
+    trusted_proxies
+        .iter()
+        .any(|proxy| proxy.parse::<IpAddr>().ok() == Some(ip))
+}
+
+/// 客户端地址；连接来自可信代理时取转发链中最后一个不可信的地址，解析不了时取连接地址
+pub(crate) fn client_ip(req: &Request, trusted_proxies: &[String]) -> Option<IpAddr> {
+    let peer = req.remote_addr().as_socket_addr()?.ip();
+    Some(forwarded_client(peer, req.headers(), trusted_proxies))
+}
+
+fn forwarded_client(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[String]) -> IpAddr {
+    if !is_trusted(peer, trusted_proxies) {
+        return peer;
+    }
+    // 从右向左跳过可信代理，更左侧的地址可能由客户端伪造
+    for ip in forwarded_chain(headers).into_iter().rev() {
+        match ip {
+            Some(ip) if is_trusted(ip, trusted_proxies) => continue,
+            Some(ip) => return ip,
+            None => break,
+        }
+    }
+    peer
+}
+
+/// 优先取 Forwarded 的 for 参数，没有时取 X-Forwarded-For
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let values = |name| {
+        headers
+            .get_all(name)
+            .into_iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+    };
+    let forwarded: Vec<_> = values(FORWARDED.as_str())
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, node) = pair.trim().split_once('=')?;
+                key.eq_ignore_ascii_case("for").then(|| parse_node(node))
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+    values("x-forwarded-for").map(parse_node).collect()
+}
+
+/// 解析 `1.2.3.4`、`1.2.3.4:80`、`"[::1]:80"` 形式的节点，`unknown` 等无法解析
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    node.parse()
+        .ok()
+        .or_else(|| node.rsplit_once(':')?.0.parse().ok())
+}
+
+/// 限定数据属于 owners 之一，没有用户标识和角色时不匹配任何数据
+pub(crate) fn owner_clause(owners: &[(&str, String)]) -> String {
+    if owners.is_empty() {
+        return "1 = 0".to_string();
+    }
+    let owner = "(`owner_type` = ? AND `owner_id` = ?)";
+    format!("({})", vec![owner; owners.len()].join(" OR "))
+}
+
+tokio::task_local! {
+    static CURRENT: User;
+}
+
+/// 当前请求的用户，不在请求处理过程中时为匿名用户
+pub(crate) fn current() -> User {
+    CURRENT.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// 从可信网关设置的 `auth.user_header` 和 `auth.role_header` 读取当前用户
+pub(crate) struct Identify;
+
+impl<E: Endpoint> Middleware<E> for Identify {
+    type Output = IdentifyEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        IdentifyEndpoint(ep)
+    }
+}
+
+pub(crate) struct IdentifyEndpoint<E>(E);
+
+impl<E: Endpoint> Endpoint for IdentifyEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let user = User::from_request(&req, &GLOBAL_CONFIG.get().unwrap().load().auth);
+        CURRENT.scope(user, self.0.call(req)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_trusted_matches_configured_proxies() {
+        let proxies = vec!["10.0.0.1".to_string(), "::1".to_string()];
+        assert!(is_trusted("10.0.0.1".parse().unwrap(), &proxies));
+        assert!(is_trusted("::ffff:10.0.0.1".parse().unwrap(), &proxies));
+        assert!(is_trusted("::1".parse().unwrap(), &proxies));
+        assert!(!is_trusted("10.0.0.2".parse().unwrap(), &proxies));
+        assert!(!is_trusted("10.0.0.1".parse().unwrap(), &[]));
+    }
+
+    #[test]
+    fn forwarded_client_skips_trusted_proxies() {
+        let proxies = vec!["10.0.0.1".to_string(), "10.0.0.2".to_string()];
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client = |pairs: &[(&'static str, &str)], peer: IpAddr| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.append(*name, value.parse().unwrap());
+            }
+            forwarded_client(peer, &headers, &proxies).to_string()
+        };
+
+        // 不是可信代理时忽略转发头
+        let other = "192.168.0.9".parse().unwrap();
+        assert_eq!(
+            client(&[("x-forwarded-for", "1.1.1.1")], other),
+            "192.168.0.9"
+        );
+        assert_eq!(client(&[], proxy), "10.0.0.1");
+        assert_eq!(
+            client(&[("x-forwarded-for", "6.6.6.6, 1.1.1.1, 10.0.0.2")], proxy),
+            "1.1.1.1"
+        );
+        assert_eq!(
+            client(
+                &[(
+                    "forwarded",
+                    "for=\"[2001:db8::1]:80\";proto=https, for=10.0.0.2"
+                )],
+                proxy
+            ),
+            "2001:db8::1"
+        );
+        assert_eq!(
+            client(
+                &[
+                    ("forwarded", "for=1.1.1.1:80"),
+                    ("x-forwarded-for", "2.2.2.2")
+                ],
+                proxy
+            ),
+            "1.1.1.1"
+        );
+        // 无法解析的节点之前的地址不可信
+        assert_eq!(
+            client(&[("x-forwarded-for", "6.6.6.6, unknown")], proxy),
+            "10.0.0.1"
+        );
+    }
+}